@@ -0,0 +1,382 @@
+//! Off-chain instruction builders, enabled with the `client` feature.
+//!
+//! Every builder derives the fundraiser PDA, contribution PDA, vault and token accounts
+//! itself and lists the accounts in the order the matching processor destructures them.
+//! Native-SOL campaigns pass [`NATIVE_SOL`] as both `mint` and `token_program`, and
+//! [`VaultKind::Pda`] as their vault kind.
+
+use solana_address::Address;
+use solana_instruction::{AccountMeta, Instruction};
+
+use crate::{
+    constants::{CONTRIBUTION_SEED, FUNDRAISER_SEED, MAKER_SEED, VAULT_SEED},
+    instructions::{
+        ContributeData, ContributionLimits, FundraiserInstruction, InitializeData, RefundData,
+        WithdrawData,
+    },
+    state::{FundingMode, VaultKind, WithdrawalPolicy},
+    vault::NATIVE_SOL,
+};
+
+pub fn find_fundraiser_address(maker: &Address, campaign_id: u64) -> (Address, u8) {
+    Address::find_program_address(
+        &[FUNDRAISER_SEED, maker.as_ref(), &campaign_id.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+/// The maker's account, which numbers their campaigns.
+pub fn find_maker_address(maker: &Address) -> (Address, u8) {
+    Address::find_program_address(&[MAKER_SEED, maker.as_ref()], &crate::ID)
+}
+
+pub fn find_contribution_address(fundraiser: &Address, contributor: &Address) -> (Address, u8) {
+    Address::find_program_address(
+        &[CONTRIBUTION_SEED, fundraiser.as_ref(), contributor.as_ref()],
+        &crate::ID,
+    )
+}
+
+/// `token_program` is the program that owns `mint`: SPL Token or Token-2022.
+pub fn find_associated_token_address(
+    wallet: &Address,
+    mint: &Address,
+    token_program: &Address,
+) -> (Address, u8) {
+    Address::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &pinocchio_associated_token_account::ID,
+    )
+}
+
+pub fn get_associated_token_address(
+    wallet: &Address,
+    mint: &Address,
+    token_program: &Address,
+) -> Address {
+    find_associated_token_address(wallet, mint, token_program).0
+}
+
+/// The `[VAULT_SEED, fundraiser]` PDA used by [`VaultKind::Pda`] vaults.
+pub fn find_vault_address(fundraiser: &Address) -> (Address, u8) {
+    Address::find_program_address(&[VAULT_SEED, fundraiser.as_ref()], &crate::ID)
+}
+
+/// The vault holding the campaign's funds and the bump of its address: the fundraiser's
+/// associated token account or the vault PDA, depending on `vault_kind`.
+pub fn find_campaign_vault_address(
+    fundraiser: &Address,
+    mint: &Address,
+    token_program: &Address,
+    vault_kind: VaultKind,
+) -> (Address, u8) {
+    match vault_kind {
+        VaultKind::AssociatedToken => {
+            find_associated_token_address(fundraiser, mint, token_program)
+        }
+        VaultKind::Pda => find_vault_address(fundraiser),
+    }
+}
+
+pub fn get_vault_address(
+    fundraiser: &Address,
+    mint: &Address,
+    token_program: &Address,
+    vault_kind: VaultKind,
+) -> Address {
+    find_campaign_vault_address(fundraiser, mint, token_program, vault_kind).0
+}
+
+/// Where `wallet` sends and receives the campaign's funds: its associated token account, or
+/// the wallet itself when `mint` is [`NATIVE_SOL`].
+pub fn get_asset_account(wallet: &Address, mint: &Address, token_program: &Address) -> Address {
+    if *mint == NATIVE_SOL {
+        return *wallet;
+    }
+    get_associated_token_address(wallet, mint, token_program)
+}
+
+/// Campaign parameters for [`initialize`]: everything in [`InitializeData`] except the
+/// bumps, which the builder derives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CampaignConfig {
+    pub campaign_id: u64,
+    pub amount_to_raise: u64,
+    pub hard_cap: u64,
+    pub start_ts: Option<i64>,
+    pub end_ts: i64,
+    pub limits: ContributionLimits,
+    pub funding_mode: FundingMode,
+    pub early_checkout: bool,
+    pub withdrawal_policy: WithdrawalPolicy,
+    pub vault_kind: VaultKind,
+}
+
+pub fn initialize(
+    maker: &Address,
+    mint: &Address,
+    token_program: &Address,
+    config: &CampaignConfig,
+) -> Instruction {
+    let (fundraiser, bump) = find_fundraiser_address(maker, config.campaign_id);
+    let (vault, vault_bump) =
+        find_campaign_vault_address(&fundraiser, mint, token_program, config.vault_kind);
+    let (maker_account, maker_bump) = find_maker_address(maker);
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*maker, true),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(pinocchio_system::ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new(maker_account, false),
+            AccountMeta::new_readonly(pinocchio_associated_token_account::ID, false),
+        ],
+        data: FundraiserInstruction::Initialize(InitializeData {
+            bump,
+            campaign_id: config.campaign_id,
+            amount_to_raise: config.amount_to_raise,
+            hard_cap: config.hard_cap,
+            start_ts: config.start_ts,
+            end_ts: config.end_ts,
+            limits: config.limits,
+            funding_mode: config.funding_mode,
+            early_checkout: config.early_checkout,
+            withdrawal_policy: config.withdrawal_policy,
+            vault_kind: config.vault_kind,
+            vault_bump,
+            maker_bump,
+        })
+        .pack(),
+    }
+}
+
+pub fn contribute(
+    contributor: &Address,
+    maker: &Address,
+    campaign_id: u64,
+    mint: &Address,
+    token_program: &Address,
+    vault_kind: VaultKind,
+    amount: u64,
+) -> Instruction {
+    let (fundraiser, _) = find_fundraiser_address(maker, campaign_id);
+    let (contribution, contribution_bump) = find_contribution_address(&fundraiser, contributor);
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*contributor, true),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(get_asset_account(contributor, mint, token_program), false),
+            AccountMeta::new(contribution, false),
+            AccountMeta::new(
+                get_vault_address(&fundraiser, mint, token_program, vault_kind),
+                false,
+            ),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(pinocchio_system::ID, false),
+            // creates the contributor's wrapped SOL account for wrapped SOL campaigns
+            AccountMeta::new_readonly(pinocchio_associated_token_account::ID, false),
+        ],
+        data: FundraiserInstruction::Contribute(ContributeData {
+            contribution_bump,
+            amount,
+        })
+        .pack(),
+    }
+}
+
+pub fn checkout(
+    maker: &Address,
+    campaign_id: u64,
+    mint: &Address,
+    token_program: &Address,
+    vault_kind: VaultKind,
+) -> Instruction {
+    let (fundraiser, _) = find_fundraiser_address(maker, campaign_id);
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*maker, true),
+            // fees withheld in a Token-2022 vault are harvested to the mint before it closes
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(
+                get_vault_address(&fundraiser, mint, token_program, vault_kind),
+                false,
+            ),
+            AccountMeta::new(get_asset_account(maker, mint, token_program), false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(pinocchio_system::ID, false),
+            AccountMeta::new_readonly(pinocchio_associated_token_account::ID, false),
+        ],
+        data: FundraiserInstruction::Checkout.pack(),
+    }
+}
+
+/// The vault accounts are only used when nothing was raised, to close the campaign.
+pub fn cancel(
+    maker: &Address,
+    campaign_id: u64,
+    mint: &Address,
+    token_program: &Address,
+    vault_kind: VaultKind,
+) -> Instruction {
+    let (fundraiser, _) = find_fundraiser_address(maker, campaign_id);
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*maker, true),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(
+                get_vault_address(&fundraiser, mint, token_program, vault_kind),
+                false,
+            ),
+            AccountMeta::new(get_asset_account(maker, mint, token_program), false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+        data: FundraiserInstruction::Cancel.pack(),
+    }
+}
+
+/// With `unwrap_sol`, a wrapped SOL campaign refunds as SOL; see [`RefundData::unwrap_sol`].
+pub fn refund(
+    contributor: &Address,
+    maker: &Address,
+    campaign_id: u64,
+    mint: &Address,
+    token_program: &Address,
+    vault_kind: VaultKind,
+    unwrap_sol: bool,
+) -> Instruction {
+    let (fundraiser, _) = find_fundraiser_address(maker, campaign_id);
+    let (contribution, contribution_bump) = find_contribution_address(&fundraiser, contributor);
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*contributor, true),
+            AccountMeta::new(*maker, false),
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(contribution, false),
+            AccountMeta::new(get_asset_account(contributor, mint, token_program), false),
+            AccountMeta::new(
+                get_vault_address(&fundraiser, mint, token_program, vault_kind),
+                false,
+            ),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(pinocchio_system::ID, false),
+            AccountMeta::new_readonly(pinocchio_associated_token_account::ID, false),
+        ],
+        data: FundraiserInstruction::Refund(RefundData {
+            contribution_bump,
+            unwrap_sol,
+        })
+        .pack(),
+    }
+}
+
+pub fn withdraw(
+    contributor: &Address,
+    maker: &Address,
+    campaign_id: u64,
+    mint: &Address,
+    token_program: &Address,
+    vault_kind: VaultKind,
+    amount: u64,
+) -> Instruction {
+    let (fundraiser, _) = find_fundraiser_address(maker, campaign_id);
+    let (contribution, contribution_bump) = find_contribution_address(&fundraiser, contributor);
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*contributor, true),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(contribution, false),
+            AccountMeta::new(get_asset_account(contributor, mint, token_program), false),
+            AccountMeta::new(
+                get_vault_address(&fundraiser, mint, token_program, vault_kind),
+                false,
+            ),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(pinocchio_system::ID, false),
+            AccountMeta::new_readonly(pinocchio_associated_token_account::ID, false),
+        ],
+        data: FundraiserInstruction::Withdraw(WithdrawData {
+            contribution_bump,
+            amount,
+        })
+        .pack(),
+    }
+}
+
+/// Builds a permissionless batch refund for `contributors` of a failed or cancelled
+/// campaign. Any account can send it as `payer`, which also pays for the wrapped SOL
+/// accounts the refunds have to recreate.
+pub fn refund_batch(
+    payer: &Address,
+    maker: &Address,
+    campaign_id: u64,
+    mint: &Address,
+    token_program: &Address,
+    vault_kind: VaultKind,
+    contributors: &[Address],
+) -> Instruction {
+    let (fundraiser, _) = find_fundraiser_address(maker, campaign_id);
+
+    let mut accounts = vec![
+        AccountMeta::new(*maker, false),
+        AccountMeta::new(*mint, false),
+        AccountMeta::new(fundraiser, false),
+        AccountMeta::new(
+            get_vault_address(&fundraiser, mint, token_program, vault_kind),
+            false,
+        ),
+        AccountMeta::new_readonly(*token_program, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(pinocchio_system::ID, false),
+        AccountMeta::new_readonly(pinocchio_associated_token_account::ID, false),
+    ];
+    for contributor in contributors {
+        let (contribution, _) = find_contribution_address(&fundraiser, contributor);
+        accounts.extend([
+            AccountMeta::new(contribution, false),
+            AccountMeta::new(*contributor, false),
+            AccountMeta::new(get_asset_account(contributor, mint, token_program), false),
+        ]);
+    }
+
+    Instruction {
+        program_id: crate::ID,
+        accounts,
+        data: FundraiserInstruction::RefundBatch.pack(),
+    }
+}
+
+/// Builds the instruction that closes `contributor`'s contribution once nothing is
+/// refundable, returning its rent.
+pub fn close_contribution(contributor: &Address, maker: &Address, campaign_id: u64) -> Instruction {
+    let (fundraiser, _) = find_fundraiser_address(maker, campaign_id);
+    let (contribution, _) = find_contribution_address(&fundraiser, contributor);
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*contributor, true),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(contribution, false),
+        ],
+        data: FundraiserInstruction::CloseContribution.pack(),
+    }
+}
@@ -1,4 +1,6 @@
 pub const MIN_AMOUNT_TO_RAISE: u64 = 3;
-pub const SECONDS_TO_DAYS: u64 = 86400;
-pub const MAX_CONTRIBUTION_PERCENTAGE: u64 = 10;
-pub const PERCENTAGE_SCALER: u64 = 100;
\ No newline at end of file
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const FUNDRAISER_SEED: &[u8] = b"fundraiser";
+pub const CONTRIBUTION_SEED: &[u8] = b"contributor";
+pub const VAULT_SEED: &[u8] = b"vault";
+pub const MAKER_SEED: &[u8] = b"maker";
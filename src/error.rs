@@ -0,0 +1,69 @@
+use pinocchio::error::ProgramError;
+
+/// Errors returned by the fundraiser program.
+///
+/// Every variant is surfaced to clients as `ProgramError::Custom(code)`. The codes are
+/// part of the program interface, so new variants must be appended and existing ones
+/// must never be renumbered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum FundraiserError {
+    /// The goal passed to initialize is below the minimum amount to raise.
+    AmountToRaiseTooLow = 0,
+    /// The fundraiser account is not the expected program derived address.
+    InvalidFundraiser = 1,
+    /// The contribution is below the minimum contribution.
+    ContributionTooSmall = 2,
+    /// The contribution would take the contributor over the per-contributor cap.
+    ContributionLimitExceeded = 3,
+    /// The fundraiser end timestamp has passed.
+    FundraiserEnded = 4,
+    /// The fundraiser end timestamp has not passed yet.
+    FundraiserNotEnded = 5,
+    /// The fundraiser hasn't raised enough for the maker to check out.
+    GoalNotReached = 6,
+    /// The fundraiser succeeded, so contributions are no longer refundable.
+    GoalReached = 7,
+    /// The account is not of the type the instruction expects.
+    InvalidAccountDiscriminator = 8,
+    /// The account was written with a layout version this program can't read.
+    UnsupportedAccountVersion = 9,
+    /// The maker account does not match the fundraiser's stored maker.
+    MakerMismatch = 10,
+    /// The vault is not at the address of the fundraiser's vault.
+    InvalidVault = 11,
+    /// An amount calculation overflowed.
+    ArithmeticOverflow = 12,
+    /// A timestamp is inconsistent with the clock or with another timestamp.
+    InvalidTimestamp = 13,
+    /// The fundraiser start timestamp has not been reached yet.
+    FundraiserNotStarted = 14,
+    /// The maker has cancelled the fundraiser.
+    FundraiserCancelled = 15,
+    /// The fundraiser's status doesn't allow this instruction.
+    InvalidStatusTransition = 16,
+    /// The contribution limits passed to initialize are inconsistent.
+    InvalidContributionLimits = 17,
+    /// The fundraiser already has its maximum number of contributors.
+    ContributorLimitReached = 18,
+    /// The fundraiser has raised its hard cap and accepts no more contributions.
+    HardCapReached = 19,
+    /// The hard cap passed to initialize is below the goal.
+    InvalidHardCap = 20,
+    /// The fundraiser doesn't let contributors withdraw.
+    WithdrawalsDisabled = 21,
+    /// The contribution's withdrawal cooling-off period is over.
+    CoolingOffExpired = 22,
+    /// The wallet passed for a contribution isn't the one that made it.
+    ContributorMismatch = 23,
+    /// The contribution belongs to an earlier campaign at the same fundraiser address.
+    StaleContribution = 24,
+    /// The mint has a transfer hook, which the program can't call.
+    UnsupportedMint = 25,
+}
+
+impl From<FundraiserError> for ProgramError {
+    fn from(e: FundraiserError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
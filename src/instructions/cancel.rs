@@ -0,0 +1,79 @@
+use pinocchio::{
+    AccountView, ProgramResult,
+    cpi::{Seed, Signer},
+    error::ProgramError,
+};
+
+use crate::{
+    constants::FUNDRAISER_SEED,
+    error::FundraiserError,
+    state::{Fundraiser, FundraiserStatus},
+    time,
+    vault::{Asset, Vault},
+};
+
+/// Abandons a running campaign. Only the stored maker can cancel, and only before the end
+/// timestamp; once cancelled, contributions and checkout are rejected and every
+/// contributor can refund straight away. A campaign holding no contributions has nobody
+/// to refund, so its vault and fundraiser account close to the maker right away.
+pub fn process_cancel(accounts: &[AccountView]) -> ProgramResult {
+    let [
+        maker,
+        fundraiser_acc,
+        mint,
+        vault_ata,
+        maker_ata,
+        token_program,
+        _remaining @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let asset = Asset::load(mint, token_program)?;
+
+    let (campaign_id, bump) = {
+        let fundraiser_state = Fundraiser::from_account_info(fundraiser_acc)?;
+        if !maker.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if fundraiser_state.maker != *maker.address().as_array() {
+            return Err(FundraiserError::MakerMismatch.into());
+        }
+        if fundraiser_state.mint != *mint.address().as_array() {
+            return Err(ProgramError::InvalidArgument);
+        }
+        match fundraiser_state.sync_status(time::now()?)? {
+            FundraiserStatus::Pending | FundraiserStatus::Active => {}
+            FundraiserStatus::Cancelled => return Err(FundraiserError::FundraiserCancelled.into()),
+            _ => return Err(FundraiserError::FundraiserEnded.into()),
+        }
+
+        fundraiser_state.transition(FundraiserStatus::Cancelled)?;
+
+        if u64::from_le_bytes(fundraiser_state.current_amount) > 0 {
+            return Ok(());
+        }
+        asset.check_vault(fundraiser_acc, fundraiser_state, vault_ata)?;
+        if !asset.is_account_of(maker_ata, maker)? {
+            return Err(ProgramError::IllegalOwner);
+        }
+        (fundraiser_state.campaign_id, fundraiser_state.bump)
+    };
+
+    let bump = [bump];
+    let seed = [
+        Seed::from(FUNDRAISER_SEED),
+        Seed::from(maker.address().as_array()),
+        Seed::from(&campaign_id),
+        Seed::from(&bump),
+    ];
+    let signer = Signer::from(&seed[..]);
+    Vault {
+        fundraiser: fundraiser_acc,
+        account: vault_ata,
+        asset,
+        signer: &signer,
+    }
+    .close_if_settled(maker, maker_ata)
+}
@@ -2,21 +2,26 @@ use pinocchio::{
     AccountView, ProgramResult,
     cpi::{Seed, Signer},
     error::ProgramError,
-    sysvars::{Sysvar, clock::Clock},
 };
 #[allow(unused)]
 use pinocchio_log::log;
 
-use crate::{constants::SECONDS_TO_DAYS, state::Fundraiser};
+use crate::{
+    constants::FUNDRAISER_SEED,
+    error::FundraiserError,
+    state::{self, Fundraiser, FundraiserStatus},
+    time,
+    vault::{Asset, Vault},
+};
 
-pub fn process_checkout(accounts: &[AccountView], _data: &[u8]) -> ProgramResult {
+pub fn process_checkout(accounts: &[AccountView]) -> ProgramResult {
     let [
         maker,
         mint,
         fundrasier_acc,
         vault_ata,
         maker_ata,
-        _token_program,
+        token_program,
         _system_program,
         _associated_token_program @ ..,
     ] = accounts
@@ -24,61 +29,78 @@ pub fn process_checkout(accounts: &[AccountView], _data: &[u8]) -> ProgramResult
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
-    let (bump, amount) = {
+    let asset = Asset::load(mint, token_program)?;
+
+    let (campaign_id, bump) = {
         let fundraiser_state = Fundraiser::from_account_info(fundrasier_acc)?;
-        let maker_ata_state = pinocchio_token::state::TokenAccount::from_account_view(maker_ata)?;
-        if !maker.is_signer() && fundraiser_state.maker != *maker.address().as_array() {
+        if !maker.is_signer() {
             return Err(ProgramError::MissingRequiredSignature);
         }
-        if maker_ata_state.owner() != maker.address() {
-            return Err(ProgramError::IllegalOwner);
+        if fundraiser_state.maker != *maker.address().as_array() {
+            return Err(FundraiserError::MakerMismatch.into());
         }
-        if maker_ata_state.mint() != mint.address() {
+        if fundraiser_state.mint != *mint.address().as_array() {
             return Err(ProgramError::InvalidArgument);
         }
 
-        assert!(
-            u64::from_le_bytes(fundraiser_state.amount_to_raise)
-                <= u64::from_le_bytes(fundraiser_state.current_amount),
-            "Amount to raise not reached"
-        );
+        match fundraiser_state.sync_status(time::now()?)? {
+            FundraiserStatus::Succeeded => {}
+            // closes the campaign early; later contributions see it as ended
+            FundraiserStatus::Active
+                if fundraiser_state.early_checkout != 0 && fundraiser_state.goal_reached() =>
+            {
+                fundraiser_state.transition(FundraiserStatus::Succeeded)?;
+            }
+            FundraiserStatus::Pending | FundraiserStatus::Active => {
+                if !fundraiser_state.is_funded()? {
+                    return Err(FundraiserError::GoalNotReached.into());
+                }
+                return Err(FundraiserError::FundraiserNotEnded.into());
+            }
+            // nobody is owed a refund, so the maker closes the empty campaign
+            FundraiserStatus::Failed
+                if u64::from_le_bytes(fundraiser_state.current_amount) == 0 => {}
+            FundraiserStatus::Failed => return Err(FundraiserError::GoalNotReached.into()),
+            FundraiserStatus::Cancelled => {
+                return Err(FundraiserError::FundraiserCancelled.into());
+            }
+            // already checked out
+            FundraiserStatus::Withdrawn | FundraiserStatus::Closed => {
+                return Err(FundraiserError::InvalidStatusTransition.into());
+            }
+        }
 
+        asset.check_vault(fundrasier_acc, fundraiser_state, vault_ata)?;
+        if !asset.is_account_of(maker_ata, maker)? {
+            return Err(ProgramError::IllegalOwner);
+        }
 
-        let current_time = Clock::get()?.unix_timestamp.to_le_bytes();
-        assert!(
-            fundraiser_state.duration
-                < ((u64::from_le_bytes(current_time)
-                    - u64::from_le_bytes(fundraiser_state.time_started))
-                    / SECONDS_TO_DAYS) as u8,
-            "Fundraise Duration is not Over"
-        );
-        (
-            fundraiser_state.bump,
-            u64::from_le_bytes(fundraiser_state.current_amount),
-        )
+        if fundraiser_state.status()? == FundraiserStatus::Succeeded {
+            fundraiser_state.transition(FundraiserStatus::Withdrawn)?;
+        }
+        (fundraiser_state.campaign_id, fundraiser_state.bump)
     };
     let bump = [bump];
     let seed = [
-        Seed::from(b"fundraiser"),
+        Seed::from(FUNDRAISER_SEED),
         Seed::from(maker.address().as_array()),
+        Seed::from(&campaign_id),
         Seed::from(&bump),
     ];
     let signer = Signer::from(&seed[..]);
 
-    pinocchio_token::instructions::Transfer {
-        from: vault_ata,
-        to: maker_ata,
-        authority: fundrasier_acc,
-        amount,
-    }
-    .invoke_signed(&[signer.clone()])?;
-
-    pinocchio_token::instructions::CloseAccount {
+    let vault = Vault {
+        fundraiser: fundrasier_acc,
         account: vault_ata,
-        destination: maker,
-        authority: fundrasier_acc,
-    }
-    .invoke_signed(&[signer])?;
+        asset,
+        signer: &signer,
+    };
+    // the vault balance, not current_amount, so tokens sent to the vault directly aren't
+    // left behind and the vault can close
+    vault.pay(maker_ata, vault.balance()?)?;
+    vault.close(maker)?;
 
-    Ok(())
+    // contributions stay open so contributors can reclaim their rent with close_contribution
+    Fundraiser::from_account_info(fundrasier_acc)?.transition(FundraiserStatus::Closed)?;
+    state::close_account(fundrasier_acc, maker)
 }
@@ -0,0 +1,58 @@
+use pinocchio::{AccountView, ProgramResult, error::ProgramError};
+
+use crate::{
+    error::FundraiserError,
+    state::{self, Contribution, Fundraiser, FundraiserStatus},
+    time,
+};
+
+/// Closes a contribution that no longer holds anything refundable and returns its rent to
+/// the contributor: either the campaign succeeded, or the fundraiser account it was made to
+/// has since been closed or re-created.
+pub fn process_close_contribution(accounts: &[AccountView]) -> ProgramResult {
+    let [
+        contributor,
+        fundraiser_acc,
+        contribution_acc,
+        _remaining @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    {
+        let contribution_state = Contribution::from_account_info(contribution_acc)?;
+        if !contributor.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if contribution_state.contributor != *contributor.address().as_array() {
+            return Err(FundraiserError::ContributorMismatch.into());
+        }
+        contribution_state.verify_address(contribution_acc, fundraiser_acc.address())?;
+
+        // a closed fundraiser has paid out everything it held, through checkout or refunds
+        if fundraiser_acc.owned_by(&crate::ID) {
+            let fundraiser_state = Fundraiser::from_account_info(fundraiser_acc)?;
+            if contribution_state.check_campaign(fundraiser_state).is_ok() {
+                match fundraiser_state.sync_status(time::now()?)? {
+                    FundraiserStatus::Succeeded | FundraiserStatus::Withdrawn => {}
+                    FundraiserStatus::Pending | FundraiserStatus::Active => {
+                        return Err(FundraiserError::FundraiserNotEnded.into());
+                    }
+                    // the contribution is still owed back; refund closes it
+                    FundraiserStatus::Failed => {
+                        return Err(FundraiserError::GoalNotReached.into());
+                    }
+                    FundraiserStatus::Cancelled => {
+                        return Err(FundraiserError::FundraiserCancelled.into());
+                    }
+                    FundraiserStatus::Closed => {
+                        return Err(FundraiserError::InvalidStatusTransition.into());
+                    }
+                }
+            }
+        }
+    }
+
+    state::close_account(contribution_acc, contributor)
+}
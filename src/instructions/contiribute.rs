@@ -2,7 +2,7 @@ use pinocchio::{
     AccountView, ProgramResult,
     cpi::{Seed, Signer},
     error::ProgramError,
-    sysvars::{Sysvar, clock::Clock, rent::Rent},
+    sysvars::{Sysvar, rent::Rent},
 };
 
 #[allow(unused)]
@@ -10,20 +10,18 @@ use pinocchio_log::log;
 
 use pinocchio_pubkey::derive_address;
 use pinocchio_system::instructions::CreateAccount;
-use wincode::SchemaRead;
 
 use crate::{
-    constants::{MAX_CONTRIBUTION_PERCENTAGE, PERCENTAGE_SCALER, SECONDS_TO_DAYS},
-    state::{Contribution, Fundraiser},
+    constants::CONTRIBUTION_SEED,
+    error::FundraiserError,
+    instructions::ContributeData,
+    limits, math,
+    state::{Contribution, Fundraiser, FundraiserStatus},
+    time, token,
+    vault::Asset,
 };
 
-#[derive(SchemaRead)]
-pub struct ContributeData {
-    contribution_bump: u8,
-    amount: u64,
-}
-
-pub fn process_contribution(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+pub fn process_contribution(accounts: &[AccountView], ix_data: ContributeData) -> ProgramResult {
     let [
         contributor,
         mint,
@@ -31,60 +29,63 @@ pub fn process_contribution(accounts: &[AccountView], data: &[u8]) -> ProgramRes
         contributor_ata,
         contribution_acc,
         vault_ata,
-        _token_program,
-        _system_program,
+        token_program,
+        system_program,
+        _remaining @ ..,
     ] = accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
     // Account validation checks
-    // 1. mint matches wiht the fundraiser stored ata and contributor ata
-    // 2. contributor pda matching
-    // 3. vault ata owner check
-    // 4. reason for not validating the fundraiser_acc its okay if the given fundraise mint is matched with the vault ata and anyways we are checking for the owner and the mint stored in that pda
-
-    let ix_data = ::wincode::deserialize::<ContributeData>(data)
-        .map_err(|_| ProgramError::InvalidInstructionData)?;
-    {
-        let contributor_ata_state =
-            pinocchio_token::state::TokenAccount::from_account_view(contributor_ata)?;
-        let vault_ata_state = pinocchio_token::state::TokenAccount::from_account_view(vault_ata)?;
-        let mint_state = pinocchio_token::state::Mint::from_account_view(mint)?;
-
-        let fundraise_data = unsafe { fundraiser_acc.borrow_unchecked() };
-        let fundraise_state = ::wincode::deserialize::<Fundraiser>(fundraise_data)
-            .map_err(|_| ProgramError::InvalidAccountData)?;
-
-        if fundraise_state.mint != *mint.address().as_array()
-            || contributor_ata_state.mint() != mint.address()
+    // 1. fundraiser_acc is a program owned fundraiser PDA (checked by the loader)
+    // 2. mint matches wiht the fundraiser stored ata and contributor ata
+    // 3. contributor pda matching
+    // 4. vault belongs to the fundraiser
+
+    let asset = Asset::load(mint, token_program)?;
+    // wrapped SOL campaigns take lamports and wrap them in the contributor's associated
+    // account, which is only kept if it existed before
+    let temporary_wsol_account = asset.is_wrapped_sol()
+        && token::create_associated_account_if_missing(
+            contributor,
+            contributor,
+            contributor_ata,
+            mint,
+            token_program,
+            system_program,
+        )?;
+
+    let (accepted_amount, vault_before, contributor_count, now) = {
+        let fundraise_state = Fundraiser::from_account_info(fundraiser_acc)?;
+
+        if fundraise_state.mint != *mint.address().as_array() {
+            return Err(ProgramError::InvalidArgument);
+        }
+        // native-SOL contributions come straight from the contributor's wallet
+        if let Asset::Token { .. } = asset
+            && token::token_account(contributor_ata)?.mint() != mint.address()
         {
             return Err(ProgramError::InvalidArgument);
         }
-        if vault_ata_state.owner() != fundraiser_acc.address() {
-            return Err(ProgramError::IllegalOwner);
+        asset.check_vault(fundraiser_acc, fundraise_state, vault_ata)?;
+
+        let now = time::now()?;
+        match fundraise_state.sync_status(now)? {
+            FundraiserStatus::Active => {}
+            FundraiserStatus::Pending => return Err(FundraiserError::FundraiserNotStarted.into()),
+            FundraiserStatus::Cancelled => {
+                return Err(FundraiserError::FundraiserCancelled.into());
+            }
+            _ => return Err(FundraiserError::FundraiserEnded.into()),
         }
 
-        assert!(
-            ix_data.amount > 1_u8.pow(mint_state.decimals() as u32) as u64,
-            "Minimum amount Decline"
-        );
-
-        let current_time = Clock::get()?.unix_timestamp.to_le_bytes();
-        assert!(
-            fundraise_state.duration
-                > ((u64::from_le_bytes(current_time)
-                    - u64::from_le_bytes(fundraise_state.time_started))
-                    / SECONDS_TO_DAYS) as u8,
-            "Fundraise Duration is Over"
-        );
-
         // Derive and verify contribution PDA
         let contribution_bump = ix_data.contribution_bump;
         let contribution_bump_bytes = [contribution_bump];
         let expected_contribution = derive_address(
             &[
-                b"contributor",
+                CONTRIBUTION_SEED,
                 fundraiser_acc.address().as_array(),
                 contributor.address().as_array(),
                 &contribution_bump_bytes,
@@ -97,7 +98,7 @@ pub fn process_contribution(accounts: &[AccountView], data: &[u8]) -> ProgramRes
         }
 
         let contribution_signer_seeds = [
-            Seed::from(b"contributor"),
+            Seed::from(CONTRIBUTION_SEED),
             Seed::from(fundraiser_acc.address().as_array()),
             Seed::from(contributor.address().as_array()),
             Seed::from(&contribution_bump_bytes),
@@ -105,6 +106,18 @@ pub fn process_contribution(accounts: &[AccountView], data: &[u8]) -> ProgramRes
         let contribution_signer = Signer::from(&contribution_signer_seeds[..]);
         let is_new_account = contribution_acc.lamports() == 0;
 
+        let previous_amount = if is_new_account {
+            None
+        } else if unsafe { contribution_acc.owner() } == &crate::ID {
+            let contribution_state = Contribution::from_account_info(contribution_acc)?;
+            contribution_state.check_campaign(fundraise_state)?;
+            Some(u64::from_le_bytes(contribution_state.amount))
+        } else {
+            return Err(ProgramError::IllegalOwner);
+        };
+        let outcome =
+            limits::evaluate_contribution(fundraise_state, previous_amount, ix_data.amount)?;
+
         if is_new_account {
             CreateAccount {
                 from: contributor,
@@ -115,32 +128,50 @@ pub fn process_contribution(accounts: &[AccountView], data: &[u8]) -> ProgramRes
             }
             .invoke_signed(&[contribution_signer])?;
 
-            let contribution_state = Contribution::from_account_info(contribution_acc)?;
-            contribution_state.amount = ix_data.amount;
-        } else if unsafe { contribution_acc.owner() } == &crate::ID {
-            let contribution_state = Contribution::from_account_info(contribution_acc)?;
-            let new_amount = contribution_state.amount + ix_data.amount;
-            assert!(
-                new_amount
-                    <= (u64::from_le_bytes(fundraise_state.amount_to_raise))
-                        * MAX_CONTRIBUTION_PERCENTAGE
-                        / PERCENTAGE_SCALER,
-                "new amount is exceeding the threshold"
-            );
-            contribution_state.amount = new_amount;
-        } else {
-            return Err(ProgramError::IllegalOwner);
+            let contribution_state = Contribution::init_account_info(contribution_acc)?;
+            contribution_state.contributor = *contributor.address().as_array();
+            contribution_state.bump = contribution_bump;
+            contribution_state.campaign_nonce = fundraise_state.campaign_nonce;
+        }
+        (
+            outcome.accepted_amount,
+            asset.balance(vault_ata)?,
+            outcome.contributor_count,
+            now,
+        )
+    };
+    if asset.is_wrapped_sol() {
+        token::wrap_sol(contributor, contributor_ata, token_program, accepted_amount)?;
+    }
+    asset.deposit(contributor, contributor_ata, vault_ata, accepted_amount)?;
+    if temporary_wsol_account {
+        token::CloseAccount {
+            account: contributor_ata,
+            destination: contributor,
+            authority: contributor,
+            token_program,
         }
-        let update = u64::from_le_bytes(fundraise_state.current_amount) + ix_data.amount;
-        Fundraiser::from_account_info(fundraiser_acc)?.current_amount = update.to_le_bytes();
+        .invoke()?;
     }
-    pinocchio_token::instructions::Transfer {
-        from: contributor_ata,
-        to: vault_ata,
-        authority: contributor,
-        amount: ix_data.amount,
+
+    // credit what the vault actually received, which is less than what was sent when the
+    // mint charges a transfer fee
+    let received = math::checked_sub(asset.balance(vault_ata)?, vault_before)?;
+    if received == 0 {
+        return Err(FundraiserError::ContributionTooSmall.into());
     }
-    .invoke()?;
+
+    let contribution_state = Contribution::from_account_info(contribution_acc)?;
+    let contributor_total =
+        math::checked_add(u64::from_le_bytes(contribution_state.amount), received)?;
+    contribution_state.amount = contributor_total.to_le_bytes();
+    contribution_state.last_contribution_ts = now.to_le_bytes();
+
+    let fundraise_state = Fundraiser::from_account_info(fundraiser_acc)?;
+    fundraise_state.contributor_count = contributor_count.to_le_bytes();
+    let current_amount =
+        math::checked_add(u64::from_le_bytes(fundraise_state.current_amount), received)?;
+    fundraise_state.current_amount = current_amount.to_le_bytes();
 
     Ok(())
 }
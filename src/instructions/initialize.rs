@@ -1,27 +1,27 @@
 use pinocchio::{
-    AccountView, ProgramResult,
+    AccountView, Address, ProgramResult,
     cpi::{Seed, Signer},
     error::ProgramError,
-    sysvars::{Sysvar, clock::Clock, rent::Rent},
+    sysvars::{Sysvar, rent::Rent},
 };
 
 #[allow(unused)]
 use pinocchio_log::log;
 
 use pinocchio_pubkey::derive_address;
-use pinocchio_system::instructions::CreateAccount;
-use wincode::SchemaRead;
+use pinocchio_system::instructions::{Allocate, Assign, CreateAccount, Transfer};
 
-use crate::{constants::MIN_AMOUNT_TO_RAISE, state::Fundraiser};
-
-#[derive(SchemaRead)]
-pub struct InitializeData {
-    bump: u8,
-    amount_to_raise: [u8; 8],
-    duration: u8,
-}
+use crate::{
+    constants::{BPS_DENOMINATOR, FUNDRAISER_SEED, MAKER_SEED, MIN_AMOUNT_TO_RAISE, VAULT_SEED},
+    error::FundraiserError,
+    instructions::{ContributionCap, ContributionLimits, InitializeData},
+    math,
+    state::{Fundraiser, FundraiserStatus, Maker, VaultKind, WithdrawalPolicy},
+    time, token,
+    vault::{self, Asset},
+};
 
-pub fn process_initialize(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+pub fn process_initialize(accounts: &[AccountView], ix_data: InitializeData) -> ProgramResult {
     let [
         maker,
         mint,
@@ -29,33 +29,68 @@ pub fn process_initialize(accounts: &[AccountView], data: &[u8]) -> ProgramResul
         vault_ata,
         system_program,
         token_program,
+        maker_acc,
         _remaining @ ..,
     ] = accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
-    let ix_data = ::wincode::deserialize::<InitializeData>(data)
-        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    let asset = Asset::load(mint, token_program)?;
+    if let Asset::Token { .. } = asset
+        && token::has_transfer_hook(mint)?
+    {
+        return Err(FundraiserError::UnsupportedMint.into());
+    }
+    // lamports can't sit in an associated token account
+    if let (Asset::Lamports, VaultKind::AssociatedToken) = (&asset, ix_data.vault_kind) {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    if ix_data.amount_to_raise <= math::checked_pow(MIN_AMOUNT_TO_RAISE, asset.decimals() as u32)? {
+        return Err(FundraiserError::AmountToRaiseTooLow.into());
+    }
+
+    if ix_data.hard_cap < ix_data.amount_to_raise {
+        return Err(FundraiserError::InvalidHardCap.into());
+    }
 
-    let mint_state = pinocchio_token::state::Mint::from_account_view(mint)?;
+    if let WithdrawalPolicy::CoolingOff(period) = ix_data.withdrawal_policy
+        && period <= 0
+    {
+        return Err(FundraiserError::InvalidTimestamp.into());
+    }
 
-    assert!(
-        u64::from_le_bytes(ix_data.amount_to_raise)
-            > MIN_AMOUNT_TO_RAISE.pow(mint_state.decimals() as u32),
-        "amount to raise less than the required MIN_AMOUNT"
-    );
+    let max_contribution = resolve_max_contribution(&ix_data.limits, ix_data.amount_to_raise)?;
+
+    let now = time::now()?;
+    let start_ts = match ix_data.start_ts {
+        // a scheduled start can't be backdated
+        Some(start_ts) if start_ts < now => return Err(FundraiserError::InvalidTimestamp.into()),
+        Some(start_ts) => start_ts,
+        None => now,
+    };
+    time::validate_window(start_ts, ix_data.end_ts)?;
 
     let bump = ix_data.bump;
-    let seed = [b"fundraiser".as_ref(), maker.address().as_ref(), &[bump]];
+    let campaign_id = ix_data.campaign_id.to_le_bytes();
+    let seed = [
+        FUNDRAISER_SEED,
+        maker.address().as_ref(),
+        &campaign_id,
+        &[bump],
+    ];
     let fundraiser_account_pda = derive_address(&seed, None, &crate::ID.to_bytes());
 
-    assert_eq!(fundraiser_account_pda, *fundraiser.address().as_array());
+    if fundraiser_account_pda != *fundraiser.address().as_array() {
+        return Err(FundraiserError::InvalidFundraiser.into());
+    }
 
     let bump = [bump];
     let seed = [
-        Seed::from(b"fundraiser"),
+        Seed::from(FUNDRAISER_SEED),
         Seed::from(maker.address().as_array()),
+        Seed::from(&campaign_id),
         Seed::from(&bump),
     ];
     let signer = Signer::from(&seed[..]);
@@ -70,33 +105,210 @@ pub fn process_initialize(accounts: &[AccountView], data: &[u8]) -> ProgramResul
                 owner: &crate::ID,
             }
             .invoke_signed(&[signer])?;
+            let campaign_nonce = count_campaign(maker, maker_acc, ix_data.maker_bump)?;
 
             {
-                let fundraiser_state = Fundraiser::from_account_info(fundraiser)?;
+                let fundraiser_state = Fundraiser::init_account_info(fundraiser)?;
 
                 fundraiser_state.maker = *maker.address().as_array();
+                fundraiser_state.campaign_id = campaign_id;
                 fundraiser_state.mint = *mint.address().as_array();
-                fundraiser_state.amount_to_raise = ix_data.amount_to_raise;
+                fundraiser_state.amount_to_raise = ix_data.amount_to_raise.to_le_bytes();
+                fundraiser_state.hard_cap = ix_data.hard_cap.to_le_bytes();
                 fundraiser_state.current_amount = [0; 8];
-                fundraiser_state.time_started = Clock::get()?.unix_timestamp.to_le_bytes(); // ???
-                fundraiser_state.duration = ix_data.duration; // ???
+                fundraiser_state.min_contribution = ix_data.limits.min_contribution.to_le_bytes();
+                fundraiser_state.max_contribution = max_contribution.to_le_bytes();
+                fundraiser_state.max_contributors = ix_data.limits.max_contributors.to_le_bytes();
+                fundraiser_state.contributor_count = [0; 4];
+                fundraiser_state.start_ts = start_ts.to_le_bytes();
+                fundraiser_state.end_ts = ix_data.end_ts.to_le_bytes();
                 fundraiser_state.bump = ix_data.bump;
+                fundraiser_state.funding_mode = ix_data.funding_mode as u8;
+                fundraiser_state.early_checkout = ix_data.early_checkout as u8;
+                fundraiser_state.set_withdrawal_policy(ix_data.withdrawal_policy);
+                fundraiser_state.campaign_nonce = campaign_nonce.to_le_bytes();
+                fundraiser_state.vault_bump = ix_data.vault_bump;
+                fundraiser_state.vault_kind = ix_data.vault_kind as u8;
+                fundraiser_state.status = if time::has_started(start_ts, now) {
+                    FundraiserStatus::Active
+                } else {
+                    FundraiserStatus::Pending
+                } as u8;
             }
         } else {
             return Err(ProgramError::IllegalOwner);
         }
     }
 
-    // we can do this client side to reduce CU
-    pinocchio_associated_token_account::instructions::Create {
-        funding_account: maker,
-        account: vault_ata,
-        wallet: fundraiser,
-        mint,
-         token_program,
-         system_program,
+    match (asset, ix_data.vault_kind) {
+        // we can do this client side to reduce CU
+        (Asset::Token { .. }, VaultKind::AssociatedToken) => {
+            // the associated token program only creates the canonical address, so this also
+            // proves the stored bump is the canonical one
+            if !token::is_associated_token_address(
+                fundraiser,
+                mint,
+                token_program,
+                ix_data.vault_bump,
+                vault_ata,
+            ) {
+                return Err(FundraiserError::InvalidVault.into());
+            }
+            pinocchio_associated_token_account::instructions::Create {
+                funding_account: maker,
+                account: vault_ata,
+                wallet: fundraiser,
+                mint,
+                token_program,
+                system_program,
+            }
+            .invoke()
+        }
+        (Asset::Token { .. }, VaultKind::Pda) => {
+            let space = token::account_len(mint, token_program)?;
+            create_vault(
+                maker,
+                fundraiser,
+                vault_ata,
+                ix_data.vault_bump,
+                space,
+                token_program.address(),
+            )?;
+            token::InitializeAccount3 {
+                account: vault_ata,
+                mint,
+                owner: fundraiser.address(),
+                token_program,
+            }
+            .invoke()
+        }
+        // a data-less, program-owned account whose lamports are the campaign's funds
+        (Asset::Lamports, _) => create_vault(
+            maker,
+            fundraiser,
+            vault_ata,
+            ix_data.vault_bump,
+            0,
+            &crate::ID,
+        ),
+    }
+}
+
+/// Counts a new campaign in the maker's account, creating the account with the maker's
+/// first campaign, and returns the campaign's nonce.
+fn count_campaign(
+    maker: &AccountView,
+    maker_acc: &AccountView,
+    bump: u8,
+) -> Result<u64, ProgramError> {
+    // anyone can send lamports to the address before the maker's first campaign
+    if maker_acc.owned_by(&pinocchio_system::ID) && maker_acc.is_data_empty() {
+        create_maker_account(maker, maker_acc, bump)?;
+    } else if !maker_acc.owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let maker_state = Maker::from_account_info(maker_acc)?;
+    // an existing account could be another maker's
+    maker_state.verify_address(maker_acc, maker.address())?;
+    let campaign_count = math::checked_add(u64::from_le_bytes(maker_state.campaign_count), 1)?;
+    maker_state.campaign_count = campaign_count.to_le_bytes();
+    Ok(campaign_count)
+}
+
+/// Creates the maker's account at the canonical `[MAKER_SEED, maker]` PDA. Any other bump
+/// would give the maker a second account whose count starts over, repeating nonces.
+fn create_maker_account(maker: &AccountView, maker_acc: &AccountView, bump: u8) -> ProgramResult {
+    let (expected_address, canonical_bump) =
+        Address::find_program_address(&[MAKER_SEED, maker.address().as_ref()], &crate::ID);
+    if bump != canonical_bump || maker_acc.address() != &expected_address {
+        return Err(ProgramError::InvalidSeeds);
     }
-    .invoke()?;
 
+    let bump = [bump];
+    let seed = [
+        Seed::from(MAKER_SEED),
+        Seed::from(maker.address().as_array()),
+        Seed::from(&bump),
+    ];
+    let signers = [Signer::from(&seed[..])];
+    // CreateAccount fails on an address that already holds lamports, so top it up to rent
+    // exemption and allocate and assign it instead
+    let missing_rent = Rent::get()?
+        .minimum_balance_unchecked(Maker::LEN)
+        .saturating_sub(maker_acc.lamports());
+    if missing_rent > 0 {
+        Transfer {
+            from: maker,
+            to: maker_acc,
+            lamports: missing_rent,
+        }
+        .invoke()?;
+    }
+    Allocate {
+        account: maker_acc,
+        space: Maker::LEN as u64,
+    }
+    .invoke_signed(&signers)?;
+    Assign {
+        account: maker_acc,
+        owner: &crate::ID,
+    }
+    .invoke_signed(&signers)?;
+    Maker::init_account_info(maker_acc)?.bump = bump[0];
     Ok(())
 }
+
+/// Creates the account at the `[VAULT_SEED, fundraiser]` PDA, funded by the maker.
+fn create_vault(
+    maker: &AccountView,
+    fundraiser: &AccountView,
+    vault: &AccountView,
+    bump: u8,
+    space: usize,
+    owner: &Address,
+) -> ProgramResult {
+    if !vault::is_vault_address(fundraiser, bump, vault) {
+        return Err(FundraiserError::InvalidVault.into());
+    }
+
+    let bump = [bump];
+    let seed = [
+        Seed::from(VAULT_SEED),
+        Seed::from(fundraiser.address().as_array()),
+        Seed::from(&bump),
+    ];
+    CreateAccount {
+        from: maker,
+        to: vault,
+        lamports: Rent::get()?.minimum_balance_unchecked(space),
+        space: space as u64,
+        owner,
+    }
+    .invoke_signed(&[Signer::from(&seed[..])])
+}
+
+/// Validates the campaign's contribution limits and returns the per-contributor cap in base
+/// units of the mint.
+fn resolve_max_contribution(
+    limits: &ContributionLimits,
+    amount_to_raise: u64,
+) -> Result<u64, ProgramError> {
+    let max_contribution = match limits.max_per_contributor {
+        ContributionCap::Amount(amount) => amount,
+        ContributionCap::BasisPoints(bps) if bps as u64 <= BPS_DENOMINATOR => {
+            math::checked_mul_div(amount_to_raise, bps as u64, BPS_DENOMINATOR)?
+        }
+        ContributionCap::BasisPoints(_) => {
+            return Err(FundraiserError::InvalidContributionLimits.into());
+        }
+    };
+
+    if limits.min_contribution == 0
+        || max_contribution < limits.min_contribution
+        || limits.max_contributors == 0
+    {
+        return Err(FundraiserError::InvalidContributionLimits.into());
+    }
+    Ok(max_contribution)
+}
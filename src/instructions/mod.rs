@@ -1,32 +1,202 @@
+pub mod cancel;
 pub mod checker;
+pub mod close_contribution;
 pub mod contiribute;
 pub mod initialize;
 pub mod refund;
+pub mod refund_batch;
+pub mod withdraw;
 
+pub use cancel::*;
 pub use checker::*;
+pub use close_contribution::*;
 pub use contiribute::*;
 pub use initialize::*;
-// pub use refund::*;
+pub use refund::*;
+pub use refund_batch::*;
+pub use withdraw::*;
 
 use pinocchio::error::ProgramError;
+use wincode::{SchemaRead, SchemaWrite, config::DefaultConfig};
 
-pub enum FundraiseInstrctions {
+use crate::state::{FundingMode, VaultKind, WithdrawalPolicy};
+
+/// Leading byte of every instruction's data.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstructionDiscriminator {
     Initialize = 0,
     Contribute = 1,
-    Refund = 2,
-    CheckContribution = 3,
+    Checkout = 2,
+    Refund = 3,
+    Cancel = 4,
+    Withdraw = 5,
+    RefundBatch = 6,
+    CloseContribution = 7,
 }
 
-impl TryFrom<&u8> for FundraiseInstrctions {
+impl TryFrom<&u8> for InstructionDiscriminator {
     type Error = ProgramError;
 
     fn try_from(value: &u8) -> Result<Self, Self::Error> {
         match value {
-            0 => Ok(FundraiseInstrctions::Initialize),
-            1 => Ok(FundraiseInstrctions::Contribute),
-            2 => Ok(FundraiseInstrctions::CheckContribution),
-            3 => Ok(FundraiseInstrctions::Refund),
+            0 => Ok(InstructionDiscriminator::Initialize),
+            1 => Ok(InstructionDiscriminator::Contribute),
+            2 => Ok(InstructionDiscriminator::Checkout),
+            3 => Ok(InstructionDiscriminator::Refund),
+            4 => Ok(InstructionDiscriminator::Cancel),
+            5 => Ok(InstructionDiscriminator::Withdraw),
+            6 => Ok(InstructionDiscriminator::RefundBatch),
+            7 => Ok(InstructionDiscriminator::CloseContribution),
             _ => Err(ProgramError::InvalidInstructionData),
         }
     }
 }
+
+/// Largest total a single contributor may give to a campaign.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, SchemaRead, SchemaWrite)]
+#[wincode(tag_encoding = "u8")]
+pub enum ContributionCap {
+    /// An absolute amount in base units of the mint.
+    Amount(u64),
+    /// A share of the goal, in basis points (at most 10_000).
+    BasisPoints(u16),
+}
+
+/// Per-campaign contribution rules, fixed at initialize.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, SchemaRead, SchemaWrite)]
+pub struct ContributionLimits {
+    /// Smallest amount accepted in a single contribution.
+    pub min_contribution: u64,
+    pub max_per_contributor: ContributionCap,
+    pub max_contributors: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, SchemaRead, SchemaWrite)]
+pub struct InitializeData {
+    pub bump: u8,
+    /// Distinguishes concurrent campaigns of the same maker; part of the fundraiser seeds.
+    pub campaign_id: u64,
+    /// The soft cap: the amount that must be raised for the maker to check out.
+    pub amount_to_raise: u64,
+    /// The most the campaign accepts in total; contributions past it are trimmed.
+    pub hard_cap: u64,
+    /// When contributions open. `None` opens the campaign immediately.
+    pub start_ts: Option<i64>,
+    pub end_ts: i64,
+    pub limits: ContributionLimits,
+    pub funding_mode: FundingMode,
+    /// Lets the maker check out before `end_ts` once the goal is reached, which ends the
+    /// campaign.
+    pub early_checkout: bool,
+    pub withdrawal_policy: WithdrawalPolicy,
+    /// Native-SOL campaigns must use [`VaultKind::Pda`].
+    pub vault_kind: VaultKind,
+    /// Bump of the vault's address: the fundraiser's associated token address or the
+    /// `[VAULT_SEED, fundraiser]` PDA, depending on `vault_kind`.
+    pub vault_bump: u8,
+    /// Canonical bump of the maker's `[MAKER_SEED, maker]` account, created with their
+    /// first campaign.
+    pub maker_bump: u8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, SchemaRead, SchemaWrite)]
+pub struct ContributeData {
+    pub contribution_bump: u8,
+    pub amount: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, SchemaRead, SchemaWrite)]
+pub struct RefundData {
+    pub contribution_bump: u8,
+    /// For wrapped SOL campaigns: pay the refund out as SOL by closing the contributor's
+    /// wrapped SOL account afterwards, which unwraps everything it holds.
+    pub unwrap_sol: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, SchemaRead, SchemaWrite)]
+pub struct WithdrawData {
+    pub contribution_bump: u8,
+    pub amount: u64,
+}
+
+/// A fundraiser instruction together with its payload.
+///
+/// This is the single source of truth for the wire format: `process_instruction` decodes
+/// with [`FundraiserInstruction::unpack`] and clients encode with [`FundraiserInstruction::pack`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FundraiserInstruction {
+    Initialize(InitializeData),
+    Contribute(ContributeData),
+    Checkout,
+    Refund(RefundData),
+    Cancel,
+    Withdraw(WithdrawData),
+    RefundBatch,
+    CloseContribution,
+}
+
+impl FundraiserInstruction {
+    pub fn discriminator(&self) -> InstructionDiscriminator {
+        match self {
+            FundraiserInstruction::Initialize(_) => InstructionDiscriminator::Initialize,
+            FundraiserInstruction::Contribute(_) => InstructionDiscriminator::Contribute,
+            FundraiserInstruction::Checkout => InstructionDiscriminator::Checkout,
+            FundraiserInstruction::Refund(_) => InstructionDiscriminator::Refund,
+            FundraiserInstruction::Cancel => InstructionDiscriminator::Cancel,
+            FundraiserInstruction::Withdraw(_) => InstructionDiscriminator::Withdraw,
+            FundraiserInstruction::RefundBatch => InstructionDiscriminator::RefundBatch,
+            FundraiserInstruction::CloseContribution => InstructionDiscriminator::CloseContribution,
+        }
+    }
+
+    pub fn unpack(instruction_data: &[u8]) -> Result<Self, ProgramError> {
+        let (discriminator, data) = instruction_data
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        Ok(match InstructionDiscriminator::try_from(discriminator)? {
+            InstructionDiscriminator::Initialize => {
+                FundraiserInstruction::Initialize(decode(data)?)
+            }
+            InstructionDiscriminator::Contribute => {
+                FundraiserInstruction::Contribute(decode(data)?)
+            }
+            InstructionDiscriminator::Checkout => FundraiserInstruction::Checkout,
+            InstructionDiscriminator::Refund => FundraiserInstruction::Refund(decode(data)?),
+            InstructionDiscriminator::Cancel => FundraiserInstruction::Cancel,
+            InstructionDiscriminator::Withdraw => FundraiserInstruction::Withdraw(decode(data)?),
+            InstructionDiscriminator::RefundBatch => FundraiserInstruction::RefundBatch,
+            InstructionDiscriminator::CloseContribution => FundraiserInstruction::CloseContribution,
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = vec![self.discriminator() as u8];
+        match self {
+            FundraiserInstruction::Initialize(data) => encode(&mut buf, data),
+            FundraiserInstruction::Contribute(data) => encode(&mut buf, data),
+            FundraiserInstruction::Checkout => {}
+            FundraiserInstruction::Refund(data) => encode(&mut buf, data),
+            FundraiserInstruction::Cancel => {}
+            FundraiserInstruction::Withdraw(data) => encode(&mut buf, data),
+            FundraiserInstruction::RefundBatch => {}
+            FundraiserInstruction::CloseContribution => {}
+        }
+        buf
+    }
+}
+
+fn decode<'de, T>(data: &'de [u8]) -> Result<T, ProgramError>
+where
+    T: SchemaRead<'de, DefaultConfig, Dst = T>,
+{
+    ::wincode::deserialize::<T>(data).map_err(|_| ProgramError::InvalidInstructionData)
+}
+
+fn encode<T>(buf: &mut Vec<u8>, data: &T)
+where
+    T: SchemaWrite<DefaultConfig, Src = T>,
+{
+    buf.extend(::wincode::serialize(data).expect("serializing into a Vec can't fail"));
+}
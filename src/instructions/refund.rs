@@ -2,23 +2,21 @@ use pinocchio::{
     AccountView, ProgramResult,
     cpi::{Seed, Signer},
     error::ProgramError,
-    sysvars::{Sysvar, clock::Clock},
 };
 use pinocchio_log::log;
 use pinocchio_pubkey::derive_address;
-use wincode::SchemaRead;
 
 use crate::{
-    constants::SECONDS_TO_DAYS,
-    state::{Contribution, Fundraiser},
+    constants::{CONTRIBUTION_SEED, FUNDRAISER_SEED},
+    error::FundraiserError,
+    instructions::RefundData,
+    math,
+    state::{Contribution, Fundraiser, FundraiserStatus},
+    time, token,
+    vault::{Asset, Vault},
 };
 
-#[derive(SchemaRead)]
-pub struct RefundData {
-    contribution_bump: u8,
-}
-
-pub fn process_refund(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+pub fn process_refund(accounts: &[AccountView], ix_data: RefundData) -> ProgramResult {
     let [
         contributor,
         maker,
@@ -27,26 +25,27 @@ pub fn process_refund(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
         contribution_acc,
         contributor_ata,
         vault_ata,
-        _token_program,
-        _system_program @ ..,
+        token_program,
+        system_program,
+        _remaining @ ..,
     ] = accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
-    let (refund_amount, bump) = {
-        let contributor_pda = unsafe { contribution_acc.borrow_unchecked() };
-        let contribution_data = ::wincode::deserialize::<Contribution>(contributor_pda)
-            .map_err(|_| ProgramError::InvalidAccountData)?;
-        let fundraise_pda = unsafe { fundraiser_acc.borrow_unchecked() };
-        let fundraiser_state = wincode::deserialize::<Fundraiser>(fundraise_pda)
-            .map_err(|_| ProgramError::InvalidInstructionData)?;
-        let ix_data = ::wincode::deserialize::<RefundData>(data)
-            .map_err(|_| ProgramError::InvalidInstructionData)?;
+    let asset = Asset::load(mint, token_program)?;
+    if ix_data.unwrap_sol && !token::is_native_mint(mint.address()) {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let (refund_amount, campaign_id, bump) = {
+        let contribution_data = Contribution::from_account_info(contribution_acc)?;
+        let fundraiser_state = Fundraiser::from_account_info(fundraiser_acc)?;
+        contribution_data.check_campaign(fundraiser_state)?;
         let contribution_bump = ix_data.contribution_bump;
         let contribution_bump_bytes = [contribution_bump];
         let expected_contribution = derive_address(
             &[
-                b"contributor",
+                CONTRIBUTION_SEED,
                 fundraiser_acc.address().as_array(),
                 contributor.address().as_array(),
                 &contribution_bump_bytes,
@@ -55,71 +54,88 @@ pub fn process_refund(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
             &crate::ID.to_bytes(),
         );
         if contribution_acc.address().as_array() != &expected_contribution {
-          return Err(ProgramError::InvalidAccountData);
+            return Err(ProgramError::InvalidAccountData);
         }
         log!("passed pda check");
         if !contributor.is_signer() {
-          return Err(ProgramError::MissingRequiredSignature);
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        // a cancelled campaign refunds immediately, whatever the clock or the amount raised
+        match fundraiser_state.sync_status(time::now()?)? {
+            FundraiserStatus::Failed | FundraiserStatus::Cancelled => {}
+            FundraiserStatus::Pending | FundraiserStatus::Active => {
+                return Err(FundraiserError::FundraiserNotEnded.into());
+            }
+            FundraiserStatus::Succeeded | FundraiserStatus::Withdrawn => {
+                return Err(FundraiserError::GoalReached.into());
+            }
+            FundraiserStatus::Closed => {
+                return Err(FundraiserError::InvalidStatusTransition.into());
+            }
         }
-        let current_time = Clock::get()?.unix_timestamp.to_le_bytes();
-        assert!(
-          fundraiser_state.duration
-          < ((u64::from_le_bytes(current_time)
-          - u64::from_le_bytes(fundraiser_state.time_started))
-          / SECONDS_TO_DAYS) as u8,
-          "Fundraise Duration is not Over"
-        );
         log!("started validating");
-        
-        assert!(
-            u64::from_le_bytes(fundraiser_state.amount_to_raise)
-                > u64::from_le_bytes(fundraiser_state.current_amount),
-            "Amount is reached"
-        );
 
         if *mint.address().as_array() != fundraiser_state.mint {
             return Err(ProgramError::InvalidAccountData);
         }
+        if *maker.address().as_array() != fundraiser_state.maker {
+            return Err(FundraiserError::MakerMismatch.into());
+        }
+        asset.check_vault(fundraiser_acc, fundraiser_state, vault_ata)?;
 
-        (contribution_data.amount, fundraiser_state.bump)
+        let refund_amount = u64::from_le_bytes(contribution_data.amount);
+        fundraiser_state.current_amount = math::checked_sub(
+            u64::from_le_bytes(fundraiser_state.current_amount),
+            refund_amount,
+        )?
+        .to_le_bytes();
+
+        (
+            refund_amount,
+            fundraiser_state.campaign_id,
+            fundraiser_state.bump,
+        )
     };
     let bump = [bump];
     let seed = [
-        Seed::from(b"fundraiser"),
+        Seed::from(FUNDRAISER_SEED),
         Seed::from(maker.address().as_array()),
+        Seed::from(&campaign_id),
         Seed::from(&bump),
     ];
     let signer = Signer::from(&seed[..]);
-    pinocchio_token::instructions::Transfer {
-        from: vault_ata,
-        to: contributor_ata,
-        authority: fundraiser_acc,
-        amount: refund_amount,
-    }
-    .invoke_signed(&[signer.clone()])?;
-
-    let contribution_lamports = contribution_acc.lamports();
-    contributor.set_lamports(contribution_lamports + contributor.lamports());
-    contribution_acc.set_lamports(0);
-
-    let close = {
-        let vault_ata_state = pinocchio_token::state::TokenAccount::from_account_view(vault_ata)?;
-        if vault_ata_state.amount() == 0 {
-            true
-        } else {
-            false
-        }
+    let vault = Vault {
+        fundraiser: fundraiser_acc,
+        account: vault_ata,
+        asset,
+        signer: &signer,
     };
-    if close {
-        pinocchio_token::instructions::CloseAccount {
-            account: vault_ata,
-            destination: maker,
-            authority: fundraiser_acc,
+    // contribute may have closed the wrapped SOL account it wrapped the lamports in
+    if vault.asset.is_wrapped_sol() {
+        token::create_associated_account_if_missing(
+            contributor,
+            contributor,
+            contributor_ata,
+            mint,
+            token_program,
+            system_program,
+        )?;
+    }
+    vault.refund(
+        contribution_acc,
+        contributor,
+        contributor_ata,
+        refund_amount,
+    )?;
+    vault.close_if_settled(maker, contributor_ata)?;
+    if ix_data.unwrap_sol {
+        token::CloseAccount {
+            account: contributor_ata,
+            destination: contributor,
+            authority: contributor,
+            token_program,
         }
-        .invoke_signed(&[signer])?;
-        let fundraise_lamports = fundraiser_acc.lamports();
-        maker.set_lamports(maker.lamports() + fundraise_lamports);
-        fundraiser_acc.set_lamports(0);
+        .invoke()?;
     }
     Ok(())
 }
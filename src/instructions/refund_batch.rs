@@ -0,0 +1,134 @@
+use pinocchio::{
+    AccountView, ProgramResult,
+    cpi::{Seed, Signer},
+    error::ProgramError,
+};
+
+use crate::{
+    constants::FUNDRAISER_SEED,
+    error::FundraiserError,
+    math,
+    state::{Contribution, Fundraiser, FundraiserStatus},
+    time, token,
+    vault::{Asset, Vault},
+};
+
+/// Refunds many contributions of a failed or cancelled campaign in one transaction. Anyone
+/// can send it: every refund goes to the token account of the wallet stored in the
+/// contribution, and the rent of each closed contribution goes back to that wallet. The
+/// batch that refunds the last contribution closes the vault and the fundraiser. For wrapped
+/// SOL campaigns, `payer` pays the rent of the contributors' wrapped SOL accounts that
+/// contribute closed again.
+///
+/// After the fixed accounts come `(contribution, contributor, contributor_ata)` triples,
+/// one per contribution to refund.
+pub fn process_refund_batch(accounts: &[AccountView]) -> ProgramResult {
+    let [
+        maker,
+        mint,
+        fundraiser_acc,
+        vault_ata,
+        token_program,
+        payer,
+        system_program,
+        _associated_token_program,
+        refunds @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if refunds.is_empty() || !refunds.len().is_multiple_of(3) {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let asset = Asset::load(mint, token_program)?;
+
+    let (campaign_id, bump, campaign_nonce) = {
+        let fundraiser_state = Fundraiser::from_account_info(fundraiser_acc)?;
+        match fundraiser_state.sync_status(time::now()?)? {
+            FundraiserStatus::Failed | FundraiserStatus::Cancelled => {}
+            FundraiserStatus::Pending | FundraiserStatus::Active => {
+                return Err(FundraiserError::FundraiserNotEnded.into());
+            }
+            FundraiserStatus::Succeeded | FundraiserStatus::Withdrawn => {
+                return Err(FundraiserError::GoalReached.into());
+            }
+            FundraiserStatus::Closed => {
+                return Err(FundraiserError::InvalidStatusTransition.into());
+            }
+        }
+        if *maker.address().as_array() != fundraiser_state.maker {
+            return Err(FundraiserError::MakerMismatch.into());
+        }
+        if *mint.address().as_array() != fundraiser_state.mint {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        asset.check_vault(fundraiser_acc, fundraiser_state, vault_ata)?;
+        (
+            fundraiser_state.campaign_id,
+            fundraiser_state.bump,
+            fundraiser_state.campaign_nonce,
+        )
+    };
+
+    let bump = [bump];
+    let seed = [
+        Seed::from(FUNDRAISER_SEED),
+        Seed::from(maker.address().as_array()),
+        Seed::from(&campaign_id),
+        Seed::from(&bump),
+    ];
+    let signer = Signer::from(&seed[..]);
+    let vault = Vault {
+        fundraiser: fundraiser_acc,
+        account: vault_ata,
+        asset,
+        signer: &signer,
+    };
+
+    let mut refunded: u64 = 0;
+    for refund in refunds.chunks_exact(3) {
+        let [contribution_acc, contributor, contributor_ata] = refund else {
+            unreachable!("chunks_exact yields triples");
+        };
+        let amount = {
+            let contribution_state = Contribution::from_account_info(contribution_acc)?;
+            contribution_state.verify_address(contribution_acc, fundraiser_acc.address())?;
+            if contribution_state.campaign_nonce != campaign_nonce {
+                return Err(FundraiserError::StaleContribution.into());
+            }
+            if contribution_state.contributor != *contributor.address().as_array() {
+                return Err(FundraiserError::ContributorMismatch.into());
+            }
+            if vault.asset.is_wrapped_sol() {
+                token::create_associated_account_if_missing(
+                    payer,
+                    contributor,
+                    contributor_ata,
+                    mint,
+                    token_program,
+                    system_program,
+                )?;
+            }
+            if !vault.asset.is_account_of(contributor_ata, contributor)? {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            u64::from_le_bytes(contribution_state.amount)
+        };
+
+        vault.refund(contribution_acc, contributor, contributor_ata, amount)?;
+        refunded = math::checked_add(refunded, amount)?;
+    }
+
+    {
+        let fundraiser_state = Fundraiser::from_account_info(fundraiser_acc)?;
+        fundraiser_state.current_amount = math::checked_sub(
+            u64::from_le_bytes(fundraiser_state.current_amount),
+            refunded,
+        )?
+        .to_le_bytes();
+    }
+    // anything sent to the vault outside of contribute goes with the last refund
+    let last_contributor_ata = &refunds[refunds.len() - 1];
+    vault.close_if_settled(maker, last_contributor_ata)
+}
@@ -0,0 +1,152 @@
+use pinocchio::{
+    AccountView, ProgramResult,
+    cpi::{Seed, Signer},
+    error::ProgramError,
+};
+use pinocchio_pubkey::derive_address;
+
+use crate::{
+    constants::{CONTRIBUTION_SEED, FUNDRAISER_SEED},
+    error::FundraiserError,
+    instructions::WithdrawData,
+    math,
+    state::{self, Contribution, Fundraiser, FundraiserStatus, WithdrawalPolicy},
+    time, token,
+    vault::{Asset, Vault},
+};
+
+/// Lets a contributor take back part or all of their contribution while the campaign is
+/// active, if the maker allows it. The contribution account is closed once it is empty.
+pub fn process_withdraw(accounts: &[AccountView], ix_data: WithdrawData) -> ProgramResult {
+    let [
+        contributor,
+        mint,
+        fundraiser_acc,
+        contribution_acc,
+        contributor_ata,
+        vault_ata,
+        token_program,
+        system_program,
+        _remaining @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let asset = Asset::load(mint, token_program)?;
+
+    let (maker, campaign_id, bump, closed) = {
+        let fundraiser_state = Fundraiser::from_account_info(fundraiser_acc)?;
+        if !contributor.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let contribution_bump_bytes = [ix_data.contribution_bump];
+        let expected_contribution = derive_address(
+            &[
+                CONTRIBUTION_SEED,
+                fundraiser_acc.address().as_array(),
+                contributor.address().as_array(),
+                &contribution_bump_bytes,
+            ],
+            None,
+            &crate::ID.to_bytes(),
+        );
+        if contribution_acc.address().as_array() != &expected_contribution {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let contribution_state = Contribution::from_account_info(contribution_acc)?;
+        contribution_state.check_campaign(fundraiser_state)?;
+
+        if fundraiser_state.mint != *mint.address().as_array() {
+            return Err(ProgramError::InvalidArgument);
+        }
+        asset.check_vault(fundraiser_acc, fundraiser_state, vault_ata)?;
+
+        let now = time::now()?;
+        match fundraiser_state.sync_status(now)? {
+            FundraiserStatus::Active => {}
+            FundraiserStatus::Pending => return Err(FundraiserError::FundraiserNotStarted.into()),
+            // cancelled campaigns pay back in full through refund
+            FundraiserStatus::Cancelled => {
+                return Err(FundraiserError::FundraiserCancelled.into());
+            }
+            _ => return Err(FundraiserError::FundraiserEnded.into()),
+        }
+
+        match fundraiser_state.withdrawal_policy()? {
+            WithdrawalPolicy::Disabled => {
+                return Err(FundraiserError::WithdrawalsDisabled.into());
+            }
+            WithdrawalPolicy::Anytime => {}
+            WithdrawalPolicy::CoolingOff(period) => {
+                let last_contribution_ts =
+                    i64::from_le_bytes(contribution_state.last_contribution_ts);
+                if time::has_ended(last_contribution_ts.saturating_add(period), now) {
+                    return Err(FundraiserError::CoolingOffExpired.into());
+                }
+            }
+        }
+
+        if ix_data.amount == 0 {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let remaining = u64::from_le_bytes(contribution_state.amount)
+            .checked_sub(ix_data.amount)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        contribution_state.amount = remaining.to_le_bytes();
+
+        fundraiser_state.current_amount = math::checked_sub(
+            u64::from_le_bytes(fundraiser_state.current_amount),
+            ix_data.amount,
+        )?
+        .to_le_bytes();
+        if remaining == 0 {
+            // frees the contributor's slot under max_contributors
+            let contributor_count = u32::from_le_bytes(fundraiser_state.contributor_count)
+                .checked_sub(1)
+                .ok_or(FundraiserError::ArithmeticOverflow)?;
+            fundraiser_state.contributor_count = contributor_count.to_le_bytes();
+        }
+
+        (
+            fundraiser_state.maker,
+            fundraiser_state.campaign_id,
+            fundraiser_state.bump,
+            remaining == 0,
+        )
+    };
+
+    let bump = [bump];
+    let seed = [
+        Seed::from(FUNDRAISER_SEED),
+        Seed::from(&maker),
+        Seed::from(&campaign_id),
+        Seed::from(&bump),
+    ];
+    let signer = Signer::from(&seed[..]);
+    // contribute may have closed the wrapped SOL account it wrapped the lamports in
+    if asset.is_wrapped_sol() {
+        token::create_associated_account_if_missing(
+            contributor,
+            contributor,
+            contributor_ata,
+            mint,
+            token_program,
+            system_program,
+        )?;
+    }
+    Vault {
+        fundraiser: fundraiser_acc,
+        account: vault_ata,
+        asset,
+        signer: &signer,
+    }
+    .pay(contributor_ata, ix_data.amount)?;
+
+    if closed {
+        state::close_account(contribution_acc, contributor)?;
+    }
+
+    Ok(())
+}
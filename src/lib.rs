@@ -3,11 +3,19 @@ use pinocchio::{
     AccountView, Address, ProgramResult, address::declare_id, entrypoint, error::ProgramError,
 };
 
-use crate::instructions::FundraiseInstrctions;
+use crate::instructions::FundraiserInstruction;
 
+#[cfg(feature = "client")]
+pub mod client;
+pub mod constants;
+pub mod error;
 pub mod instructions;
+pub mod limits;
+pub mod math;
 pub mod state;
-pub mod constants;
+pub mod time;
+pub mod token;
+pub mod vault;
 
 entrypoint!(process_instruction);
 
@@ -18,25 +26,31 @@ pub fn process_instruction(
     accounts: &[AccountView],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    assert_eq!(program_id, &ID);
+    if program_id != &ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
 
-    //get the DESCRIMINATOR from the Instruction Data
-    let (descriminator, data) = instruction_data
-        .split_first()
-        .ok_or(ProgramError::InvalidInstructionData)?;
-    match FundraiseInstrctions::try_from(descriminator)? {
-        FundraiseInstrctions::Initialize => {
+    match FundraiserInstruction::unpack(instruction_data)? {
+        FundraiserInstruction::Initialize(data) => {
             instructions::initialize::process_initialize(accounts, data)?
         }
-        FundraiseInstrctions::Contribute => {
+        FundraiserInstruction::Contribute(data) => {
             instructions::contiribute::process_contribution(accounts, data)?
         }
-        FundraiseInstrctions::CheckContribution => {
-            instructions::checker::process_checkout(accounts, data)?
-        }
-        FundraiseInstrctions::Refund => {
+        FundraiserInstruction::Checkout => instructions::checker::process_checkout(accounts)?,
+        FundraiserInstruction::Refund(data) => {
             instructions::refund::process_refund(accounts, data)?
         }
+        FundraiserInstruction::Cancel => instructions::cancel::process_cancel(accounts)?,
+        FundraiserInstruction::Withdraw(data) => {
+            instructions::withdraw::process_withdraw(accounts, data)?
+        }
+        FundraiserInstruction::RefundBatch => {
+            instructions::refund_batch::process_refund_batch(accounts)?
+        }
+        FundraiserInstruction::CloseContribution => {
+            instructions::close_contribution::process_close_contribution(accounts)?
+        }
     };
     Ok(())
 }
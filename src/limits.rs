@@ -0,0 +1,60 @@
+use pinocchio::error::ProgramError;
+
+use crate::{error::FundraiserError, math, state::Fundraiser};
+
+/// What a contribution does to the fundraiser once it has passed the campaign's limits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ContributionOutcome {
+    /// The part of the requested amount the campaign takes, trimmed to the hard cap.
+    pub accepted_amount: u64,
+    /// The contributor's running total, to be stored in their contribution account.
+    pub contributor_total: u64,
+    /// The fundraiser's contributor count, including this contributor.
+    pub contributor_count: u32,
+}
+
+/// Checks a contribution of `amount` against the fundraiser's limits. `previous_amount` is
+/// the contributor's running total, or `None` if their contribution account doesn't exist
+/// yet; a first contribution is held to exactly the same limits as a top-up.
+///
+/// A contribution that would take the campaign past its hard cap is trimmed to the room
+/// left, so the last contributor can fill the campaign exactly. Once it is full, every
+/// contribution is rejected.
+pub fn evaluate_contribution(
+    fundraiser: &Fundraiser,
+    previous_amount: Option<u64>,
+    amount: u64,
+) -> Result<ContributionOutcome, ProgramError> {
+    if amount < u64::from_le_bytes(fundraiser.min_contribution) {
+        return Err(FundraiserError::ContributionTooSmall.into());
+    }
+
+    let mut contributor_count = u32::from_le_bytes(fundraiser.contributor_count);
+    if previous_amount.is_none() {
+        if contributor_count >= u32::from_le_bytes(fundraiser.max_contributors) {
+            return Err(FundraiserError::ContributorLimitReached.into());
+        }
+        // below max_contributors, so it can't overflow
+        contributor_count += 1;
+    }
+
+    let room = math::checked_sub(
+        u64::from_le_bytes(fundraiser.hard_cap),
+        u64::from_le_bytes(fundraiser.current_amount),
+    )?;
+    if room == 0 {
+        return Err(FundraiserError::HardCapReached.into());
+    }
+    let accepted_amount = amount.min(room);
+
+    let contributor_total = math::checked_add(previous_amount.unwrap_or(0), accepted_amount)?;
+    if contributor_total > u64::from_le_bytes(fundraiser.max_contribution) {
+        return Err(FundraiserError::ContributionLimitExceeded.into());
+    }
+
+    Ok(ContributionOutcome {
+        accepted_amount,
+        contributor_total,
+        contributor_count,
+    })
+}
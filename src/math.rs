@@ -0,0 +1,27 @@
+use pinocchio::error::ProgramError;
+
+use crate::error::FundraiserError;
+
+pub fn checked_add(a: u64, b: u64) -> Result<u64, ProgramError> {
+    a.checked_add(b)
+        .ok_or_else(|| FundraiserError::ArithmeticOverflow.into())
+}
+
+pub fn checked_sub(a: u64, b: u64) -> Result<u64, ProgramError> {
+    a.checked_sub(b)
+        .ok_or_else(|| FundraiserError::ArithmeticOverflow.into())
+}
+
+pub fn checked_pow(base: u64, exp: u32) -> Result<u64, ProgramError> {
+    base.checked_pow(exp)
+        .ok_or_else(|| FundraiserError::ArithmeticOverflow.into())
+}
+
+/// `value * numerator / denominator`, computed in `u128` so only the result has to fit.
+pub fn checked_mul_div(value: u64, numerator: u64, denominator: u64) -> Result<u64, ProgramError> {
+    (value as u128)
+        .checked_mul(numerator as u128)
+        .and_then(|product| product.checked_div(denominator as u128))
+        .and_then(|result| u64::try_from(result).ok())
+        .ok_or_else(|| FundraiserError::ArithmeticOverflow.into())
+}
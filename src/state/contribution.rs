@@ -1,20 +1,95 @@
-use pinocchio::{AccountView, error::ProgramError};
+use pinocchio::{AccountView, Address, error::ProgramError};
+use pinocchio_pubkey::derive_address;
 use wincode::SchemaRead;
 
+use super::{AccountDiscriminator, Fundraiser, check_header};
+use crate::{constants::CONTRIBUTION_SEED, error::FundraiserError};
+
+#[repr(C)]
 #[derive(SchemaRead)]
 pub struct Contribution {
-    pub amount: u64,
+    pub discriminator: u8,
+    pub version: u8,
+    pub amount: [u8; 8],
+    /// When the contributor last added to `amount`; starts the withdrawal cooling-off period.
+    pub last_contribution_ts: [u8; 8],
+    /// Wallet that made the contribution and receives its refund and rent.
+    pub contributor: [u8; 32],
+    pub bump: u8,
+    /// [`Fundraiser::campaign_nonce`] of the campaign this contribution was made to.
+    pub campaign_nonce: [u8; 8],
 }
 
 impl Contribution {
-    pub const LEN: usize = 8;
+    pub const LEN: usize = core::mem::size_of::<Self>();
+    pub const DISCRIMINATOR: AccountDiscriminator = AccountDiscriminator::Contribution;
+    pub const VERSION: u8 = 1;
+
+    /// Loads an initialized contribution, rejecting accounts of another owner, type or layout.
+    #[allow(clippy::mut_from_ref)]
     pub fn from_account_info(account_info: &AccountView) -> Result<&mut Self, ProgramError> {
+        if !account_info.owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let state = Self::from_account_info_unchecked(account_info)?;
+        check_header(
+            state.discriminator,
+            state.version,
+            Self::DISCRIMINATOR,
+            Self::VERSION,
+        )?;
+        Ok(state)
+    }
+
+    /// Checks that `account_info` lives at the `[CONTRIBUTION_SEED, fundraiser, contributor,
+    /// bump]` address derived from the stored contributor and bump.
+    pub fn verify_address(
+        &self,
+        account_info: &AccountView,
+        fundraiser: &Address,
+    ) -> Result<(), ProgramError> {
+        let expected_address = derive_address(
+            &[
+                CONTRIBUTION_SEED,
+                fundraiser.as_array(),
+                &self.contributor,
+                &[self.bump],
+            ],
+            None,
+            &crate::ID.to_bytes(),
+        );
+        if account_info.address().as_array() != &expected_address {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(())
+    }
+
+    /// Rejects a contribution made to an earlier, since closed, campaign that lived at the
+    /// same fundraiser address.
+    pub fn check_campaign(&self, fundraiser: &Fundraiser) -> Result<(), ProgramError> {
+        if self.campaign_nonce != fundraiser.campaign_nonce {
+            return Err(FundraiserError::StaleContribution.into());
+        }
+        Ok(())
+    }
+
+    /// Stamps the discriminator and version on a freshly created contribution account.
+    #[allow(clippy::mut_from_ref)]
+    pub fn init_account_info(account_info: &AccountView) -> Result<&mut Self, ProgramError> {
+        let state = Self::from_account_info_unchecked(account_info)?;
+        state.discriminator = Self::DISCRIMINATOR as u8;
+        state.version = Self::VERSION;
+        Ok(state)
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    fn from_account_info_unchecked(account_info: &AccountView) -> Result<&mut Self, ProgramError> {
         let mut data = account_info.try_borrow_mut()?;
         if data.len() != Contribution::LEN {
             return Err(ProgramError::InvalidAccountData);
         }
 
-        if (data.as_ptr() as usize) % core::mem::align_of::<Self>() != 0 {
+        if !(data.as_ptr() as usize).is_multiple_of(core::mem::align_of::<Self>()) {
             return Err(ProgramError::InvalidAccountData);
         }
 
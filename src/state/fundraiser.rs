@@ -1,26 +1,298 @@
 use pinocchio::{AccountView, error::ProgramError};
-use wincode::SchemaRead;
+use pinocchio_pubkey::derive_address;
+use wincode::{SchemaRead, SchemaWrite};
 
+use super::{AccountDiscriminator, check_header};
+use crate::{constants::FUNDRAISER_SEED, error::FundraiserError, time};
+
+/// Lifecycle status stored in [`Fundraiser::status`]. Values are persisted on chain, so new
+/// statuses must be appended.
+///
+/// ```text
+/// Pending ──> Active ──> Succeeded ──> Withdrawn ──> Closed
+///    │          ├──────> Failed ─────────────────────┤
+///    └──────────┴──────> Cancelled ──────────────────┘
+/// ```
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FundraiserStatus {
+    /// Accepting contributions.
+    Active = 0,
+    /// Abandoned by the maker; contributors can refund.
+    Cancelled = 1,
+    /// Created with a start timestamp that hasn't been reached yet.
+    Pending = 2,
+    /// Ended with the goal reached; the maker can check out.
+    Succeeded = 3,
+    /// Ended without reaching the goal; contributors can refund.
+    Failed = 4,
+    /// The maker has checked out the raised funds.
+    Withdrawn = 5,
+    /// Settled; the vault and the fundraiser account are being closed.
+    Closed = 6,
+}
+
+impl FundraiserStatus {
+    pub fn can_transition_to(self, next: FundraiserStatus) -> bool {
+        use FundraiserStatus::*;
+        matches!(
+            (self, next),
+            (Pending, Active)
+                | (Pending, Cancelled)
+                | (Active, Succeeded)
+                | (Active, Failed)
+                | (Active, Cancelled)
+                | (Succeeded, Withdrawn)
+                | (Withdrawn, Closed)
+                | (Failed, Closed)
+                | (Cancelled, Closed)
+        )
+    }
+}
+
+impl TryFrom<u8> for FundraiserStatus {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FundraiserStatus::Active),
+            1 => Ok(FundraiserStatus::Cancelled),
+            2 => Ok(FundraiserStatus::Pending),
+            3 => Ok(FundraiserStatus::Succeeded),
+            4 => Ok(FundraiserStatus::Failed),
+            5 => Ok(FundraiserStatus::Withdrawn),
+            6 => Ok(FundraiserStatus::Closed),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// What happens to the funds when the campaign ends, stored in [`Fundraiser::funding_mode`].
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, SchemaRead, SchemaWrite)]
+#[wincode(tag_encoding = "u8")]
+pub enum FundingMode {
+    /// The maker only gets the funds if the goal is reached; otherwise contributors refund.
+    AllOrNothing = 0,
+    /// The maker gets whatever was raised; contributors only refund if the maker cancels.
+    KeepWhatYouRaise = 1,
+}
+
+impl TryFrom<u8> for FundingMode {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FundingMode::AllOrNothing),
+            1 => Ok(FundingMode::KeepWhatYouRaise),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// Where the campaign keeps its funds, stored in [`Fundraiser::vault_kind`].
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, SchemaRead, SchemaWrite)]
+#[wincode(tag_encoding = "u8")]
+pub enum VaultKind {
+    /// The fundraiser's associated token account. Not available to native-SOL campaigns.
+    AssociatedToken = 0,
+    /// An account at the `[VAULT_SEED, fundraiser]` PDA: a token account created without
+    /// the associated token program, or the lamport vault of a native-SOL campaign.
+    Pda = 1,
+}
+
+impl TryFrom<u8> for VaultKind {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(VaultKind::AssociatedToken),
+            1 => Ok(VaultKind::Pda),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// Whether contributors may pull back their contributions while the campaign is active.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, SchemaRead, SchemaWrite)]
+#[wincode(tag_encoding = "u8")]
+pub enum WithdrawalPolicy {
+    Disabled,
+    Anytime,
+    /// Only within this many seconds of the contributor's latest contribution.
+    CoolingOff(i64),
+}
+
+#[repr(C)]
 #[derive(Clone, Copy, PartialEq, SchemaRead)]
 pub struct Fundraiser {
+    pub discriminator: u8,
+    pub version: u8,
     pub maker: [u8; 32],
+    pub campaign_id: [u8; 8],
     pub mint: [u8; 32],
+    /// Soft cap: the success threshold for checkout and refunds.
     pub amount_to_raise: [u8; 8],
+    /// Ceiling on `current_amount`.
+    pub hard_cap: [u8; 8],
     pub current_amount: [u8; 8],
-    pub time_started: [u8; 8],
-    pub duration: u8,
+    /// Smallest single contribution.
+    pub min_contribution: [u8; 8],
+    /// Largest total per contributor, resolved to base units at initialize.
+    pub max_contribution: [u8; 8],
+    pub max_contributors: [u8; 4],
+    pub contributor_count: [u8; 4],
+    pub start_ts: [u8; 8],
+    pub end_ts: [u8; 8],
     pub bump: u8,
+    pub status: u8,
+    pub funding_mode: u8,
+    /// Non-zero if the maker may check out as soon as the goal is reached.
+    pub early_checkout: u8,
+    /// [`WithdrawalPolicy`] tag: 0 disabled, 1 anytime, 2 cooling-off.
+    pub withdrawal_policy: u8,
+    /// Cooling-off period in seconds, when `withdrawal_policy` is 2.
+    pub cooling_off_period: [u8; 8],
+    /// The maker's campaign count once this campaign was initialized, unique among the
+    /// maker's campaigns. Copied into every contribution so contributions left over from a
+    /// closed campaign at the same address aren't mistaken for this one's.
+    pub campaign_nonce: [u8; 8],
+    /// Bump of the vault's address, which instructions derive to check the vault they are
+    /// passed.
+    pub vault_bump: u8,
+    pub vault_kind: u8,
 }
 
 impl Fundraiser {
-    pub const LEN: usize = 90;
+    pub const LEN: usize = core::mem::size_of::<Self>();
+    pub const DISCRIMINATOR: AccountDiscriminator = AccountDiscriminator::Fundraiser;
+    pub const VERSION: u8 = 1;
+
+    /// Loads an initialized fundraiser. The account must be owned by this program, carry
+    /// the fundraiser header and live at the `[FUNDRAISER_SEED, maker, campaign_id, bump]`
+    /// address derived from its own stored maker, campaign id and bump.
+    #[allow(clippy::mut_from_ref)]
     pub fn from_account_info(account_info: &AccountView) -> Result<&mut Self, ProgramError> {
+        if !account_info.owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let state = Self::from_account_info_unchecked(account_info)?;
+        check_header(
+            state.discriminator,
+            state.version,
+            Self::DISCRIMINATOR,
+            Self::VERSION,
+        )?;
+
+        let expected_address = derive_address(
+            &[
+                FUNDRAISER_SEED,
+                &state.maker,
+                &state.campaign_id,
+                &[state.bump],
+            ],
+            None,
+            &crate::ID.to_bytes(),
+        );
+        if account_info.address().as_array() != &expected_address {
+            return Err(FundraiserError::InvalidFundraiser.into());
+        }
+        Ok(state)
+    }
+
+    pub fn status(&self) -> Result<FundraiserStatus, ProgramError> {
+        FundraiserStatus::try_from(self.status)
+    }
+
+    pub fn funding_mode(&self) -> Result<FundingMode, ProgramError> {
+        FundingMode::try_from(self.funding_mode)
+    }
+
+    pub fn goal_reached(&self) -> bool {
+        u64::from_le_bytes(self.current_amount) >= u64::from_le_bytes(self.amount_to_raise)
+    }
+
+    /// Whether the amount raised lets the maker check out once the campaign ends.
+    pub fn is_funded(&self) -> Result<bool, ProgramError> {
+        Ok(match self.funding_mode()? {
+            FundingMode::AllOrNothing => self.goal_reached(),
+            FundingMode::KeepWhatYouRaise => u64::from_le_bytes(self.current_amount) > 0,
+        })
+    }
+
+    pub fn vault_kind(&self) -> Result<VaultKind, ProgramError> {
+        VaultKind::try_from(self.vault_kind)
+    }
+
+    pub fn withdrawal_policy(&self) -> Result<WithdrawalPolicy, ProgramError> {
+        match self.withdrawal_policy {
+            0 => Ok(WithdrawalPolicy::Disabled),
+            1 => Ok(WithdrawalPolicy::Anytime),
+            2 => Ok(WithdrawalPolicy::CoolingOff(i64::from_le_bytes(
+                self.cooling_off_period,
+            ))),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    pub fn set_withdrawal_policy(&mut self, policy: WithdrawalPolicy) {
+        let (tag, period) = match policy {
+            WithdrawalPolicy::Disabled => (0, 0),
+            WithdrawalPolicy::Anytime => (1, 0),
+            WithdrawalPolicy::CoolingOff(period) => (2, period),
+        };
+        self.withdrawal_policy = tag;
+        self.cooling_off_period = period.to_le_bytes();
+    }
+
+    /// Moves to `next`, rejecting anything that isn't an edge of the lifecycle.
+    pub fn transition(&mut self, next: FundraiserStatus) -> Result<(), ProgramError> {
+        if !self.status()?.can_transition_to(next) {
+            return Err(FundraiserError::InvalidStatusTransition.into());
+        }
+        self.status = next as u8;
+        Ok(())
+    }
+
+    /// Applies the transitions driven by the clock: Pending becomes Active once `start_ts`
+    /// is reached, and Active becomes Succeeded or Failed once `end_ts` is reached. Every
+    /// processor syncs before deciding what the campaign allows.
+    pub fn sync_status(&mut self, now: i64) -> Result<FundraiserStatus, ProgramError> {
+        if self.status()? == FundraiserStatus::Pending
+            && time::has_started(i64::from_le_bytes(self.start_ts), now)
+        {
+            self.transition(FundraiserStatus::Active)?;
+        }
+        if self.status()? == FundraiserStatus::Active
+            && time::has_ended(i64::from_le_bytes(self.end_ts), now)
+        {
+            if self.is_funded()? {
+                self.transition(FundraiserStatus::Succeeded)?;
+            } else {
+                self.transition(FundraiserStatus::Failed)?;
+            }
+        }
+        self.status()
+    }
+
+    /// Stamps the discriminator and version on a freshly created fundraiser account.
+    #[allow(clippy::mut_from_ref)]
+    pub fn init_account_info(account_info: &AccountView) -> Result<&mut Self, ProgramError> {
+        let state = Self::from_account_info_unchecked(account_info)?;
+        state.discriminator = Self::DISCRIMINATOR as u8;
+        state.version = Self::VERSION;
+        Ok(state)
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    fn from_account_info_unchecked(account_info: &AccountView) -> Result<&mut Self, ProgramError> {
         let mut data = account_info.try_borrow_mut()?;
         if data.len() != Fundraiser::LEN {
             return Err(ProgramError::InvalidAccountData);
         }
 
-        if (data.as_ptr() as usize) % core::mem::align_of::<Self>() != 0 {
+        if !(data.as_ptr() as usize).is_multiple_of(core::mem::align_of::<Self>()) {
             return Err(ProgramError::InvalidAccountData);
         }
 
@@ -0,0 +1,83 @@
+use pinocchio::{AccountView, Address, error::ProgramError};
+use pinocchio_pubkey::derive_address;
+use wincode::SchemaRead;
+
+use super::{AccountDiscriminator, check_header};
+use crate::constants::MAKER_SEED;
+
+/// Per-maker account at the canonical `[MAKER_SEED, maker]` PDA, created with the maker's
+/// first campaign and never closed, so it outlives every fundraiser account of the maker.
+#[repr(C)]
+#[derive(SchemaRead)]
+pub struct Maker {
+    pub discriminator: u8,
+    pub version: u8,
+    /// Campaigns the maker has initialized. Each campaign takes the incremented count as
+    /// its [`Fundraiser::campaign_nonce`](super::Fundraiser::campaign_nonce).
+    pub campaign_count: [u8; 8],
+    pub bump: u8,
+}
+
+impl Maker {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+    pub const DISCRIMINATOR: AccountDiscriminator = AccountDiscriminator::Maker;
+    pub const VERSION: u8 = 1;
+
+    /// Loads an initialized maker account, rejecting accounts of another owner, type or
+    /// layout.
+    #[allow(clippy::mut_from_ref)]
+    pub fn from_account_info(account_info: &AccountView) -> Result<&mut Self, ProgramError> {
+        if !account_info.owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let state = Self::from_account_info_unchecked(account_info)?;
+        check_header(
+            state.discriminator,
+            state.version,
+            Self::DISCRIMINATOR,
+            Self::VERSION,
+        )?;
+        Ok(state)
+    }
+
+    /// Checks that `account_info` lives at the `[MAKER_SEED, maker, bump]` address derived
+    /// from the stored bump.
+    pub fn verify_address(
+        &self,
+        account_info: &AccountView,
+        maker: &Address,
+    ) -> Result<(), ProgramError> {
+        let expected_address = derive_address(
+            &[MAKER_SEED, maker.as_array(), &[self.bump]],
+            None,
+            &crate::ID.to_bytes(),
+        );
+        if account_info.address().as_array() != &expected_address {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(())
+    }
+
+    /// Stamps the discriminator and version on a freshly created maker account.
+    #[allow(clippy::mut_from_ref)]
+    pub fn init_account_info(account_info: &AccountView) -> Result<&mut Self, ProgramError> {
+        let state = Self::from_account_info_unchecked(account_info)?;
+        state.discriminator = Self::DISCRIMINATOR as u8;
+        state.version = Self::VERSION;
+        Ok(state)
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    fn from_account_info_unchecked(account_info: &AccountView) -> Result<&mut Self, ProgramError> {
+        let mut data = account_info.try_borrow_mut()?;
+        if data.len() != Maker::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if !(data.as_ptr() as usize).is_multiple_of(core::mem::align_of::<Self>()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self) })
+    }
+}
@@ -1,5 +1,47 @@
-pub mod fundraiser;
 pub mod contribution;
+pub mod fundraiser;
+pub mod maker;
 
+pub use contribution::*;
 pub use fundraiser::*;
-pub use contribution::*;
\ No newline at end of file
+pub use maker::*;
+
+use pinocchio::{AccountView, ProgramResult, error::ProgramError};
+
+use crate::{error::FundraiserError, math};
+
+/// Leading byte of every account owned by the program. Zero is never used so that a
+/// freshly allocated account can't be mistaken for an initialized one.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccountDiscriminator {
+    Fundraiser = 1,
+    Contribution = 2,
+    Maker = 3,
+}
+
+fn check_header(
+    discriminator: u8,
+    version: u8,
+    expected_discriminator: AccountDiscriminator,
+    expected_version: u8,
+) -> Result<(), ProgramError> {
+    if discriminator != expected_discriminator as u8 {
+        return Err(FundraiserError::InvalidAccountDiscriminator.into());
+    }
+    if version != expected_version {
+        return Err(FundraiserError::UnsupportedAccountVersion.into());
+    }
+    Ok(())
+}
+
+/// Closes a program-owned account, moving its lamports to `destination`. The data is
+/// truncated, so the account can't be loaded again later in the same transaction.
+pub fn close_account(account: &AccountView, destination: &AccountView) -> ProgramResult {
+    destination.set_lamports(math::checked_add(
+        destination.lamports(),
+        account.lamports(),
+    )?);
+    account.set_lamports(0);
+    account.close()
+}
@@ -0,0 +1,29 @@
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{Sysvar, clock::Clock},
+};
+
+use crate::error::FundraiserError;
+
+pub fn now() -> Result<i64, ProgramError> {
+    Ok(Clock::get()?.unix_timestamp)
+}
+
+/// Checks that a campaign window is non-empty, i.e. `end_ts` is strictly after `start_ts`.
+pub fn validate_window(start_ts: i64, end_ts: i64) -> Result<(), ProgramError> {
+    if end_ts <= start_ts {
+        return Err(FundraiserError::InvalidTimestamp.into());
+    }
+    Ok(())
+}
+
+/// Whether a campaign starting at `start_ts` accepts contributions at `now`.
+pub fn has_started(start_ts: i64, now: i64) -> bool {
+    now >= start_ts
+}
+
+/// Whether a campaign ending at `end_ts` is over at `now`. `end_ts` itself is the first
+/// second at which the campaign is over.
+pub fn has_ended(end_ts: i64, now: i64) -> bool {
+    now >= end_ts
+}
@@ -0,0 +1,365 @@
+//! Token program access that works with both SPL Token and Token-2022.
+//!
+//! `pinocchio_token` hardcodes the SPL Token program id in its CPIs and account loaders, so
+//! the few instructions and layouts the program needs are written out here against whichever
+//! token program owns the campaign's mint. Token-2022 accounts share the SPL Token base
+//! layout and append their extensions after it.
+
+use pinocchio::{
+    AccountView, Address, ProgramResult,
+    account::Ref,
+    address::address,
+    cpi::{Signer, get_return_data, invoke_signed},
+    error::ProgramError,
+    instruction::{InstructionAccount, InstructionView},
+};
+use pinocchio_pubkey::derive_address;
+use pinocchio_token::state::{Mint, TokenAccount};
+
+pub const TOKEN_2022_PROGRAM_ID: Address = address!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Wrapped SOL: SPL Token's native mint, whose accounts hold their balance as lamports.
+pub const NATIVE_MINT: Address = address!("So11111111111111111111111111111111111111112");
+/// Token-2022's native mint.
+pub const NATIVE_MINT_2022: Address = address!("9pan9bMn5HatX4EJdBwg9VgCa7Uz5HL8N1m5D3NdXejP");
+
+/// Token-2022 `AccountType` byte written right after the base layout of extended accounts.
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+/// Token-2022 extension type of `TransferFeeAmount`, the fees withheld in a token account.
+const TRANSFER_FEE_AMOUNT_EXTENSION: u16 = 2;
+/// Token-2022 extension type of `TransferHook`, a program every transfer of the mint calls.
+const TRANSFER_HOOK_EXTENSION: u16 = 14;
+
+const TRANSFER_CHECKED: u8 = 12;
+const CLOSE_ACCOUNT: u8 = 9;
+const SYNC_NATIVE: u8 = 17;
+const INITIALIZE_ACCOUNT_3: u8 = 18;
+const GET_ACCOUNT_DATA_SIZE: u8 = 21;
+const TRANSFER_FEE_EXTENSION: u8 = 26;
+const HARVEST_WITHHELD_TOKENS_TO_MINT: u8 = 4;
+
+pub fn is_token_program(program_id: &Address) -> bool {
+    *program_id == pinocchio_token::ID || *program_id == TOKEN_2022_PROGRAM_ID
+}
+
+pub fn is_native_mint(mint: &Address) -> bool {
+    *mint == NATIVE_MINT || *mint == NATIVE_MINT_2022
+}
+
+/// Checks that `token_program` is a supported token program and the one that owns `mint`.
+pub fn check_token_program(token_program: &AccountView, mint: &AccountView) -> ProgramResult {
+    if !is_token_program(token_program.address()) || !mint.owned_by(token_program.address()) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Whether `account` is `wallet`'s associated token account for `mint`, whose address has
+/// the bump `bump`.
+pub fn is_associated_token_address(
+    wallet: &AccountView,
+    mint: &AccountView,
+    token_program: &AccountView,
+    bump: u8,
+    account: &AccountView,
+) -> bool {
+    let expected_account = derive_address(
+        &[
+            wallet.address().as_array(),
+            token_program.address().as_array(),
+            mint.address().as_array(),
+            &[bump],
+        ],
+        None,
+        &pinocchio_associated_token_account::ID.to_bytes(),
+    );
+    account.address().as_array() == &expected_account
+}
+
+/// Loads a mint owned by either token program.
+pub fn mint(account: &AccountView) -> Result<Ref<'_, Mint>, ProgramError> {
+    if !is_token_program(unsafe { account.owner() }) || account.data_len() < Mint::LEN {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(Ref::map(account.try_borrow()?, |data| unsafe {
+        Mint::from_bytes_unchecked(data)
+    }))
+}
+
+/// Loads a token account owned by either token program.
+pub fn token_account(account: &AccountView) -> Result<Ref<'_, TokenAccount>, ProgramError> {
+    if !is_token_program(unsafe { account.owner() }) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let data = account.try_borrow()?;
+    // extended Token-2022 mints are padded to the same length, so only the type byte
+    // tells them apart from token accounts
+    let is_token_account = match data.len() {
+        len if len == TokenAccount::LEN => true,
+        len if len > TokenAccount::LEN => data[TokenAccount::LEN] == ACCOUNT_TYPE_ACCOUNT,
+        _ => false,
+    };
+    if !is_token_account {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(Ref::map(data, |data| unsafe {
+        TokenAccount::from_bytes_unchecked(data)
+    }))
+}
+
+/// Transfer fees withheld in a Token-2022 account; zero for accounts without the extension.
+pub fn withheld_amount(account: &AccountView) -> Result<u64, ProgramError> {
+    let data = account.try_borrow()?;
+    match extension(&data, TRANSFER_FEE_AMOUNT_EXTENSION)? {
+        Some(withheld) => Ok(u64::from_le_bytes(
+            withheld
+                .try_into()
+                .map_err(|_| ProgramError::InvalidAccountData)?,
+        )),
+        None => Ok(0),
+    }
+}
+
+/// Whether `mint` has a transfer hook. Transfers of such mints need the hook's accounts,
+/// which the program doesn't pass along.
+pub fn has_transfer_hook(mint: &AccountView) -> Result<bool, ProgramError> {
+    Ok(extension(&mint.try_borrow()?, TRANSFER_HOOK_EXTENSION)?.is_some())
+}
+
+/// The value of a Token-2022 extension of a mint or token account. Both are laid out the
+/// same past the base layouts: mints are padded to the length of a token account.
+fn extension(data: &[u8], extension_type: u16) -> Result<Option<&[u8]>, ProgramError> {
+    // extensions are type-length-value entries after the base layout and the type byte
+    let mut extensions = data.get(TokenAccount::LEN + 1..).unwrap_or(&[]);
+    while let [t0, t1, l0, l1, rest @ ..] = extensions {
+        let len = u16::from_le_bytes([*l0, *l1]) as usize;
+        let value = rest.get(..len).ok_or(ProgramError::InvalidAccountData)?;
+        if u16::from_le_bytes([*t0, *t1]) == extension_type {
+            return Ok(Some(value));
+        }
+        extensions = &rest[len..];
+    }
+    Ok(None)
+}
+
+/// Size of a token account for `mint`. Token-2022 mints can require extensions in their
+/// token accounts, so Token-2022 is asked with `GetAccountDataSize`.
+pub fn account_len(mint: &AccountView, token_program: &AccountView) -> Result<usize, ProgramError> {
+    if *token_program.address() == pinocchio_token::ID {
+        return Ok(TokenAccount::LEN);
+    }
+    invoke_signed(
+        &InstructionView {
+            program_id: token_program.address(),
+            accounts: &[InstructionAccount::readonly(mint.address())],
+            data: &[GET_ACCOUNT_DATA_SIZE],
+        },
+        &[mint],
+        &[],
+    )?;
+    let return_data = get_return_data()
+        .filter(|return_data| return_data.program_id() == token_program.address())
+        .ok_or(ProgramError::InvalidAccountData)?;
+    let len: [u8; 8] = return_data
+        .as_slice()
+        .try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    Ok(u64::from_le_bytes(len) as usize)
+}
+
+/// Creates `wallet`'s associated token account for `mint` if it doesn't exist yet, with
+/// `payer` paying the rent. Returns whether it was created.
+pub fn create_associated_account_if_missing(
+    payer: &AccountView,
+    wallet: &AccountView,
+    account: &AccountView,
+    mint: &AccountView,
+    token_program: &AccountView,
+    system_program: &AccountView,
+) -> Result<bool, ProgramError> {
+    if !account.owned_by(&pinocchio_system::ID) {
+        return Ok(false);
+    }
+    pinocchio_associated_token_account::instructions::Create {
+        funding_account: payer,
+        account,
+        wallet,
+        mint,
+        system_program,
+        token_program,
+    }
+    .invoke()?;
+    Ok(true)
+}
+
+/// Wraps `amount` of `wallet`'s lamports into its wrapped SOL account `account`.
+pub fn wrap_sol(
+    wallet: &AccountView,
+    account: &AccountView,
+    token_program: &AccountView,
+    amount: u64,
+) -> ProgramResult {
+    pinocchio_system::instructions::Transfer {
+        from: wallet,
+        to: account,
+        lamports: amount,
+    }
+    .invoke()?;
+    SyncNative {
+        account,
+        token_program,
+    }
+    .invoke()
+}
+
+/// Moves `amount` base units from `from` to `to`, checked against the mint's decimals.
+pub struct TransferChecked<'a> {
+    pub from: &'a AccountView,
+    pub mint: &'a AccountView,
+    pub to: &'a AccountView,
+    pub authority: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+impl TransferChecked<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let accounts = [
+            InstructionAccount::writable(self.from.address()),
+            InstructionAccount::readonly(self.mint.address()),
+            InstructionAccount::writable(self.to.address()),
+            InstructionAccount::readonly_signer(self.authority.address()),
+        ];
+        let mut data = [0u8; 10];
+        data[0] = TRANSFER_CHECKED;
+        data[1..9].copy_from_slice(&self.amount.to_le_bytes());
+        data[9] = self.decimals;
+
+        invoke_signed(
+            &InstructionView {
+                program_id: self.token_program.address(),
+                accounts: &accounts,
+                data: &data,
+            },
+            &[self.from, self.mint, self.to, self.authority],
+            signers,
+        )
+    }
+}
+
+/// Closes an empty token account, sending its rent to `destination`.
+pub struct CloseAccount<'a> {
+    pub account: &'a AccountView,
+    pub destination: &'a AccountView,
+    pub authority: &'a AccountView,
+    pub token_program: &'a AccountView,
+}
+
+impl CloseAccount<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let accounts = [
+            InstructionAccount::writable(self.account.address()),
+            InstructionAccount::writable(self.destination.address()),
+            InstructionAccount::readonly_signer(self.authority.address()),
+        ];
+
+        invoke_signed(
+            &InstructionView {
+                program_id: self.token_program.address(),
+                accounts: &accounts,
+                data: &[CLOSE_ACCOUNT],
+            },
+            &[self.account, self.destination, self.authority],
+            signers,
+        )
+    }
+}
+
+/// Moves the transfer fees withheld in `source` to its Token-2022 mint. Anyone can harvest.
+pub struct HarvestWithheldTokensToMint<'a> {
+    pub mint: &'a AccountView,
+    pub source: &'a AccountView,
+    pub token_program: &'a AccountView,
+}
+
+impl HarvestWithheldTokensToMint<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        let accounts = [
+            InstructionAccount::writable(self.mint.address()),
+            InstructionAccount::writable(self.source.address()),
+        ];
+
+        invoke_signed(
+            &InstructionView {
+                program_id: self.token_program.address(),
+                accounts: &accounts,
+                data: &[TRANSFER_FEE_EXTENSION, HARVEST_WITHHELD_TOKENS_TO_MINT],
+            },
+            &[self.mint, self.source],
+            &[],
+        )
+    }
+}
+
+/// Updates a wrapped SOL account's token balance to the lamports it holds above rent.
+pub struct SyncNative<'a> {
+    pub account: &'a AccountView,
+    pub token_program: &'a AccountView,
+}
+
+impl SyncNative<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        let accounts = [InstructionAccount::writable(self.account.address())];
+
+        invoke_signed(
+            &InstructionView {
+                program_id: self.token_program.address(),
+                accounts: &accounts,
+                data: &[SYNC_NATIVE],
+            },
+            &[self.account],
+            &[],
+        )
+    }
+}
+
+/// Initializes a token account for `mint` owned by `owner`. The account must already be
+/// allocated to the token program.
+pub struct InitializeAccount3<'a> {
+    pub account: &'a AccountView,
+    pub mint: &'a AccountView,
+    pub owner: &'a Address,
+    pub token_program: &'a AccountView,
+}
+
+impl InitializeAccount3<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        let accounts = [
+            InstructionAccount::writable(self.account.address()),
+            InstructionAccount::readonly(self.mint.address()),
+        ];
+        let mut data = [0u8; 33];
+        data[0] = INITIALIZE_ACCOUNT_3;
+        data[1..].copy_from_slice(self.owner.as_ref());
+
+        invoke_signed(
+            &InstructionView {
+                program_id: self.token_program.address(),
+                accounts: &accounts,
+                data: &data,
+            },
+            &[self.account, self.mint],
+            &[],
+        )
+    }
+}
@@ -0,0 +1,288 @@
+use pinocchio::{
+    AccountView, Address, ProgramResult,
+    cpi::Signer,
+    error::ProgramError,
+    sysvars::{Sysvar, rent::Rent},
+};
+use pinocchio_pubkey::derive_address;
+
+use crate::{
+    constants::VAULT_SEED,
+    error::FundraiserError,
+    math,
+    state::{self, Fundraiser, FundraiserStatus, VaultKind},
+    token,
+};
+
+/// Stands in for the mint of native-SOL campaigns, which raise lamports instead of tokens.
+/// Passed in the `mint` and `token_program` slots of their instructions and stored as
+/// [`Fundraiser::mint`].
+pub const NATIVE_SOL: Address = pinocchio_system::ID;
+
+/// Decimals of SOL, used where token campaigns read the mint's.
+pub const NATIVE_SOL_DECIMALS: u8 = 9;
+
+/// Whether `vault` sits at the `[VAULT_SEED, fundraiser, bump]` PDA.
+pub fn is_vault_address(fundraiser: &AccountView, bump: u8, vault: &AccountView) -> bool {
+    let expected_vault = derive_address(
+        &[VAULT_SEED, fundraiser.address().as_array(), &[bump]],
+        None,
+        &crate::ID.to_bytes(),
+    );
+    vault.address().as_array() == &expected_vault
+}
+
+/// What a campaign raises, read from the `mint` and `token_program` accounts.
+pub enum Asset<'a> {
+    /// Tokens held in a token account owned by the fundraiser, at the fundraiser's
+    /// associated token address or the vault PDA.
+    Token {
+        mint: &'a AccountView,
+        token_program: &'a AccountView,
+        decimals: u8,
+    },
+    /// Lamports held in a program-owned account at the vault PDA.
+    /// Instructions take the wallets themselves where token campaigns take their token
+    /// accounts.
+    Lamports,
+}
+
+impl<'a> Asset<'a> {
+    pub fn load(
+        mint: &'a AccountView,
+        token_program: &'a AccountView,
+    ) -> Result<Self, ProgramError> {
+        if *mint.address() == NATIVE_SOL {
+            return Ok(Asset::Lamports);
+        }
+        token::check_token_program(token_program, mint)?;
+        let decimals = token::mint(mint)?.decimals();
+        Ok(Asset::Token {
+            mint,
+            token_program,
+            decimals,
+        })
+    }
+
+    /// Whether the campaign raises wrapped SOL. Contributors' wrapped SOL accounts may not
+    /// exist: contribute wraps lamports in a temporary one.
+    pub fn is_wrapped_sol(&self) -> bool {
+        matches!(self, Asset::Token { mint, .. } if token::is_native_mint(mint.address()))
+    }
+
+    pub fn decimals(&self) -> u8 {
+        match self {
+            Asset::Token { decimals, .. } => *decimals,
+            Asset::Lamports => NATIVE_SOL_DECIMALS,
+        }
+    }
+
+    /// Checks that `vault` is the vault of `fundraiser`, by deriving its address from the
+    /// bump stored at initialize. Only the associated token program can create an account
+    /// at the associated token address, and only this program at the vault PDA, so the
+    /// address alone identifies the vault.
+    pub fn check_vault(
+        &self,
+        fundraiser_acc: &AccountView,
+        fundraiser: &Fundraiser,
+        vault: &AccountView,
+    ) -> ProgramResult {
+        let valid = match (self, fundraiser.vault_kind()?) {
+            (_, VaultKind::Pda) => is_vault_address(fundraiser_acc, fundraiser.vault_bump, vault),
+            (
+                Asset::Token {
+                    mint,
+                    token_program,
+                    ..
+                },
+                VaultKind::AssociatedToken,
+            ) => token::is_associated_token_address(
+                fundraiser_acc,
+                mint,
+                token_program,
+                fundraiser.vault_bump,
+                vault,
+            ),
+            (Asset::Lamports, VaultKind::AssociatedToken) => false,
+        };
+        if !valid {
+            return Err(FundraiserError::InvalidVault.into());
+        }
+        Ok(())
+    }
+
+    /// Whether `account` can receive this asset on behalf of `owner`: `owner`'s token
+    /// account for the mint, or `owner` itself for lamports.
+    pub fn is_account_of(
+        &self,
+        account: &AccountView,
+        owner: &AccountView,
+    ) -> Result<bool, ProgramError> {
+        Ok(match self {
+            Asset::Token { mint, .. } => {
+                let account_state = token::token_account(account)?;
+                account_state.owner() == owner.address() && account_state.mint() == mint.address()
+            }
+            Asset::Lamports => account.address() == owner.address(),
+        })
+    }
+
+    /// What `vault` holds. Lamport vaults keep their rent-exempt minimum on top, which is
+    /// only released when the vault closes.
+    pub fn balance(&self, vault: &AccountView) -> Result<u64, ProgramError> {
+        match self {
+            Asset::Token { .. } => Ok(token::token_account(vault)?.amount()),
+            Asset::Lamports => {
+                math::checked_sub(vault.lamports(), Rent::get()?.minimum_balance_unchecked(0))
+            }
+        }
+    }
+
+    /// Moves `amount` into `vault` from `from_account`, signed by its owner `from`.
+    pub fn deposit(
+        &self,
+        from: &AccountView,
+        from_account: &AccountView,
+        vault: &AccountView,
+        amount: u64,
+    ) -> ProgramResult {
+        match self {
+            Asset::Token {
+                mint,
+                token_program,
+                decimals,
+            } => token::TransferChecked {
+                from: from_account,
+                mint,
+                to: vault,
+                authority: from,
+                token_program,
+                amount,
+                decimals: *decimals,
+            }
+            .invoke(),
+            Asset::Lamports => pinocchio_system::instructions::Transfer {
+                from,
+                to: vault,
+                lamports: amount,
+            }
+            .invoke(),
+        }
+    }
+}
+
+/// The campaign's vault together with everything needed to pay out of it.
+///
+/// The vault's balance is the source of truth for what a campaign can pay: contributions
+/// are credited with what the vault actually received, and checkout sweeps whatever it
+/// holds.
+pub struct Vault<'a> {
+    pub fundraiser: &'a AccountView,
+    pub account: &'a AccountView,
+    pub asset: Asset<'a>,
+    pub signer: &'a Signer<'a, 'a>,
+}
+
+impl Vault<'_> {
+    pub fn balance(&self) -> Result<u64, ProgramError> {
+        self.asset.balance(self.account)
+    }
+
+    /// Sends `amount` out of the vault. Transfer fees, if the mint charges any, come out of
+    /// what `to` receives.
+    pub fn pay(&self, to: &AccountView, amount: u64) -> ProgramResult {
+        match &self.asset {
+            Asset::Token {
+                mint,
+                token_program,
+                decimals,
+            } => token::TransferChecked {
+                from: self.account,
+                mint,
+                to,
+                authority: self.fundraiser,
+                token_program,
+                amount,
+                decimals: *decimals,
+            }
+            .invoke_signed(core::slice::from_ref(self.signer)),
+            // the program owns the vault, so it debits it directly
+            Asset::Lamports => {
+                if amount > self.balance()? {
+                    return Err(ProgramError::InsufficientFunds);
+                }
+                self.account.set_lamports(self.account.lamports() - amount);
+                to.set_lamports(math::checked_add(to.lamports(), amount)?);
+                Ok(())
+            }
+        }
+    }
+
+    /// Pays a contribution back to the contributor's account and closes the contribution
+    /// account, returning its rent to the contributor's wallet.
+    pub fn refund(
+        &self,
+        contribution_acc: &AccountView,
+        contributor: &AccountView,
+        contributor_account: &AccountView,
+        amount: u64,
+    ) -> ProgramResult {
+        self.pay(contributor_account, amount)?;
+        state::close_account(contribution_acc, contributor)
+    }
+
+    /// Closes the empty vault and sends its rent to `destination`. Token vaults first move
+    /// any withheld transfer fees to the mint since Token-2022 refuses to close an account
+    /// that still holds them.
+    pub fn close(&self, destination: &AccountView) -> ProgramResult {
+        match &self.asset {
+            Asset::Token {
+                mint,
+                token_program,
+                ..
+            } => {
+                if token::withheld_amount(self.account)? > 0 {
+                    token::HarvestWithheldTokensToMint {
+                        mint,
+                        source: self.account,
+                        token_program,
+                    }
+                    .invoke()?;
+                }
+                token::CloseAccount {
+                    account: self.account,
+                    destination,
+                    authority: self.fundraiser,
+                    token_program,
+                }
+                .invoke_signed(core::slice::from_ref(self.signer))
+            }
+            Asset::Lamports => state::close_account(self.account, destination),
+        }
+    }
+
+    /// Once refunds have paid back everything the campaign raised, or a cancelled campaign
+    /// raised nothing, closes the vault and the fundraiser account and returns their rent to
+    /// the maker. Settlement is read from
+    /// `current_amount` rather than the vault balance, which anyone can add to; whatever
+    /// was sent to the vault outside of contribute goes to `leftover_to` so it can close.
+    pub fn close_if_settled(
+        &self,
+        maker: &AccountView,
+        leftover_to: &AccountView,
+    ) -> ProgramResult {
+        {
+            let fundraiser = Fundraiser::from_account_info(self.fundraiser)?;
+            if u64::from_le_bytes(fundraiser.current_amount) > 0 {
+                return Ok(());
+            }
+            fundraiser.transition(FundraiserStatus::Closed)?;
+        }
+        let leftover = self.balance()?;
+        if leftover > 0 {
+            self.pay(leftover_to, leftover)?;
+        }
+        self.close(maker)?;
+        state::close_account(self.fundraiser, maker)
+    }
+}
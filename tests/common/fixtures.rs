@@ -1,12 +1,55 @@
-use litesvm_token::spl_token;
+use pinocchio_fundraiser::{
+    instructions::{ContributionCap, ContributionLimits},
+    state::{AccountDiscriminator, FundingMode, Fundraiser, FundraiserStatus, VaultKind},
+};
 use solana_sdk::pubkey::Pubkey;
 
 pub const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
-pub const TOKEN_PROGRAM_ID: Pubkey = spl_token::ID;
+/// SPL Token; Token-2022 campaigns use `setup_with_token_program`.
+pub const TOKEN_PROGRAM_ID: Pubkey = pinocchio_token::ID;
 pub const AMOUNT_TO_RAISE: u64 = 100_000_000;
-pub const DURATION_IN_DAYS: u8 = 5;
-pub const DONATION_AMOUNT:u64 = 500_000;
+pub const HARD_CAP: u64 = 2 * AMOUNT_TO_RAISE;
+pub const CAMPAIGN_ID: u64 = 1;
+pub const START_TS: i64 = 1_000;
+pub const END_TS: i64 = START_TS + 5 * 86_400;
+pub const DONATION_AMOUNT: u64 = 500_000;
+/// Permissive defaults: a single contributor may fund the whole goal.
+pub const CONTRIBUTION_LIMITS: ContributionLimits = ContributionLimits {
+    min_contribution: 1_000,
+    max_per_contributor: ContributionCap::BasisPoints(10_000),
+    max_contributors: 100,
+};
 
 pub fn program_id() -> Pubkey {
-    Pubkey::from(pinocchio_fundraiser::ID)
+    pinocchio_fundraiser::ID
+}
+
+/// An active, empty campaign for tests that work on the account state directly. Tests
+/// override the fields they care about with struct update syntax.
+pub fn fundraiser_state() -> Fundraiser {
+    Fundraiser {
+        discriminator: AccountDiscriminator::Fundraiser as u8,
+        version: Fundraiser::VERSION,
+        maker: [1; 32],
+        campaign_id: CAMPAIGN_ID.to_le_bytes(),
+        mint: [2; 32],
+        amount_to_raise: AMOUNT_TO_RAISE.to_le_bytes(),
+        hard_cap: u64::MAX.to_le_bytes(),
+        current_amount: 0u64.to_le_bytes(),
+        min_contribution: CONTRIBUTION_LIMITS.min_contribution.to_le_bytes(),
+        max_contribution: AMOUNT_TO_RAISE.to_le_bytes(),
+        max_contributors: CONTRIBUTION_LIMITS.max_contributors.to_le_bytes(),
+        contributor_count: 0u32.to_le_bytes(),
+        start_ts: START_TS.to_le_bytes(),
+        end_ts: END_TS.to_le_bytes(),
+        bump: 255,
+        status: FundraiserStatus::Active as u8,
+        funding_mode: FundingMode::AllOrNothing as u8,
+        early_checkout: 0,
+        withdrawal_policy: 0,
+        cooling_off_period: [0; 8],
+        campaign_nonce: 1u64.to_le_bytes(),
+        vault_bump: 255,
+        vault_kind: VaultKind::AssociatedToken as u8,
+    }
 }
@@ -1,41 +1,42 @@
-use solana_sdk::message::{AccountMeta, Instruction};
+use litesvm::types::TransactionResult;
+use pinocchio_fundraiser::client::{self, CampaignConfig};
+use solana_sdk::message::Instruction;
+use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signer::Signer;
 
-use crate::fixtures::TOKEN_PROGRAM_ID;
-use crate::utils::send_transaction;
+use crate::utils::{send_transaction, try_send_transaction};
 use crate::{
     TestContext,
-    fixtures::{AMOUNT_TO_RAISE, DURATION_IN_DAYS, program_id},
+    fixtures::{AMOUNT_TO_RAISE, END_TS},
 };
 
+pub fn initialize_ix(
+    ctx: &TestContext,
+    amount_to_raise: u64,
+    start_ts: Option<i64>,
+    end_ts: i64,
+) -> Instruction {
+    client::initialize(
+        &ctx.maker.pubkey(),
+        &ctx.mint,
+        &ctx.token_program,
+        &CampaignConfig {
+            campaign_id: ctx.campaign_id,
+            amount_to_raise,
+            hard_cap: ctx.hard_cap,
+            start_ts,
+            end_ts,
+            limits: ctx.limits,
+            funding_mode: ctx.funding_mode,
+            early_checkout: ctx.early_checkout,
+            withdrawal_policy: ctx.withdrawal_policy,
+            vault_kind: ctx.vault_kind,
+        },
+    )
+}
+
 pub fn send_initialize_transaction(ctx: &mut TestContext) {
-    let amount_to_raise_bytes: [u8; 8] = {
-        let mut arr = [0u8; 8];
-        arr[..8].copy_from_slice(&AMOUNT_TO_RAISE.to_le_bytes());
-        arr
-    };
-
-    let init_data = [
-        vec![0u8],
-        ctx.fundraiser_bump.to_le_bytes().to_vec(),
-        amount_to_raise_bytes.to_vec(),
-        DURATION_IN_DAYS.to_le_bytes().to_vec(),
-    ]
-    .concat();
-
-    let init_ix = Instruction {
-        program_id: program_id(),
-        accounts: vec![
-            AccountMeta::new(ctx.maker.pubkey(), true),
-            AccountMeta::new(ctx.mint, false),
-            AccountMeta::new(ctx.fundraiser, false),
-            AccountMeta::new(ctx.vault_ata, false),
-            AccountMeta::new(ctx.system_program, false),
-            AccountMeta::new(TOKEN_PROGRAM_ID, false),
-            AccountMeta::new(ctx.associated_token_program, false),
-        ],
-        data: init_data,
-    };
+    let init_ix = initialize_ix(ctx, AMOUNT_TO_RAISE, None, END_TS);
 
     let maker_pubkey = ctx.maker.pubkey();
 
@@ -46,34 +47,31 @@ pub fn send_initialize_transaction(ctx: &mut TestContext) {
     send_transaction(&mut ctx.svm, init_ix, &[&ctx.maker], &maker_pubkey);
 }
 
+pub fn try_initialize_transaction(
+    ctx: &mut TestContext,
+    amount_to_raise: u64,
+    start_ts: Option<i64>,
+    end_ts: i64,
+) -> TransactionResult {
+    let init_ix = initialize_ix(ctx, amount_to_raise, start_ts, end_ts);
+    let maker_pubkey = ctx.maker.pubkey();
+    try_send_transaction(&mut ctx.svm, init_ix, &[&ctx.maker], &maker_pubkey)
+}
+
+pub fn contribution_ix(ctx: &TestContext, amount: u64) -> Instruction {
+    client::contribute(
+        &ctx.donar.pubkey(),
+        &ctx.maker.pubkey(),
+        ctx.campaign_id,
+        &ctx.mint,
+        &ctx.token_program,
+        ctx.vault_kind,
+        amount,
+    )
+}
+
 pub fn send_contribution_transaction(ctx: &mut TestContext, amount: u64) {
-    let amount_bytes: [u8; 8] = {
-        let mut arr = [0u8; 8];
-        arr[..8].copy_from_slice(&amount.to_le_bytes());
-        arr
-    };
-
-    let contribution_data = [
-        vec![1u8],
-        ctx.contribution_bump.to_le_bytes().to_vec(),
-        amount_bytes.to_vec(),
-    ]
-    .concat();
-
-    let contribution_ix = Instruction {
-        program_id: program_id(),
-        accounts: vec![
-            AccountMeta::new(ctx.donar.pubkey(), true),
-            AccountMeta::new(ctx.mint, false),
-            AccountMeta::new(ctx.fundraiser, false),
-            AccountMeta::new(ctx.donar_ata, false),
-            AccountMeta::new(ctx.contribution, false),
-            AccountMeta::new(ctx.vault_ata, false),
-            AccountMeta::new(TOKEN_PROGRAM_ID, false),
-            AccountMeta::new(ctx.system_program, false),
-        ],
-        data: contribution_data,
-    };
+    let contribution_ix = contribution_ix(ctx, amount);
 
     let contributor_pubkey = ctx.donar.pubkey();
 
@@ -89,23 +87,29 @@ pub fn send_contribution_transaction(ctx: &mut TestContext, amount: u64) {
     );
 }
 
+pub fn try_contribution_transaction(ctx: &mut TestContext, amount: u64) -> TransactionResult {
+    let contribution_ix = contribution_ix(ctx, amount);
+    let contributor_pubkey = ctx.donar.pubkey();
+    try_send_transaction(
+        &mut ctx.svm,
+        contribution_ix,
+        &[&ctx.donar],
+        &contributor_pubkey,
+    )
+}
+
+pub fn checkout_ix(ctx: &TestContext) -> Instruction {
+    client::checkout(
+        &ctx.maker.pubkey(),
+        ctx.campaign_id,
+        &ctx.mint,
+        &ctx.token_program,
+        ctx.vault_kind,
+    )
+}
+
 pub fn send_checkout_transaction(ctx: &mut TestContext) {
-    let ix_data = [vec![2u8]].concat();
-
-    let checkout_inx = Instruction {
-        program_id: program_id(),
-        accounts: vec![
-            AccountMeta::new(ctx.maker.pubkey(), true),
-            AccountMeta::new(ctx.mint, false),
-            AccountMeta::new(ctx.fundraiser, false),
-            AccountMeta::new(ctx.vault_ata, false),
-            AccountMeta::new(ctx.maker_ata, false),
-            AccountMeta::new(TOKEN_PROGRAM_ID, false),
-            AccountMeta::new(ctx.system_program, false),
-            AccountMeta::new(ctx.associated_token_program, false),
-        ],
-        data: ix_data,
-    };
+    let checkout_inx = checkout_ix(ctx);
 
     let maker_pubkey = ctx.maker.pubkey();
 
@@ -116,26 +120,129 @@ pub fn send_checkout_transaction(ctx: &mut TestContext) {
     send_transaction(&mut ctx.svm, checkout_inx, &[&ctx.maker], &maker_pubkey);
 }
 
+pub fn try_checkout_transaction(ctx: &mut TestContext) -> TransactionResult {
+    let checkout_inx = checkout_ix(ctx);
+    let maker_pubkey = ctx.maker.pubkey();
+    try_send_transaction(&mut ctx.svm, checkout_inx, &[&ctx.maker], &maker_pubkey)
+}
+
+pub fn cancel_ix(ctx: &TestContext) -> Instruction {
+    client::cancel(
+        &ctx.maker.pubkey(),
+        ctx.campaign_id,
+        &ctx.mint,
+        &ctx.token_program,
+        ctx.vault_kind,
+    )
+}
+
+pub fn send_cancel_transaction(ctx: &mut TestContext) {
+    let cancel_ix = cancel_ix(ctx);
+    let maker_pubkey = ctx.maker.pubkey();
+    send_transaction(&mut ctx.svm, cancel_ix, &[&ctx.maker], &maker_pubkey);
+}
+
+pub fn try_cancel_transaction(ctx: &mut TestContext) -> TransactionResult {
+    let cancel_ix = cancel_ix(ctx);
+    let maker_pubkey = ctx.maker.pubkey();
+    try_send_transaction(&mut ctx.svm, cancel_ix, &[&ctx.maker], &maker_pubkey)
+}
+
+pub fn refund_ix(ctx: &TestContext) -> Instruction {
+    client::refund(
+        &ctx.donar.pubkey(),
+        &ctx.maker.pubkey(),
+        ctx.campaign_id,
+        &ctx.mint,
+        &ctx.token_program,
+        ctx.vault_kind,
+        false,
+    )
+}
+
 pub fn send_refund_transaction(ctx: &mut TestContext) {
-    let ix_data = [vec![3u8], ctx.contribution_bump.to_le_bytes().to_vec()].concat();
-    let refund_ix = Instruction {
-        program_id: program_id(),
-        accounts: vec![
-            AccountMeta::new(ctx.donar.pubkey(), true),
-            AccountMeta::new(ctx.maker.pubkey(), false),
-            AccountMeta::new(ctx.mint, false),
-            AccountMeta::new(ctx.fundraiser, false),
-            AccountMeta::new(ctx.contribution, false),
-            AccountMeta::new(ctx.donar_ata, false),
-            AccountMeta::new(ctx.vault_ata, false),
-            AccountMeta::new(TOKEN_PROGRAM_ID, false),
-            AccountMeta::new(ctx.system_program, false),
-        ],
-        data: ix_data,
-    };
+    let refund_ix = refund_ix(ctx);
     let contributor_pubkey = ctx.donar.pubkey();
     for (index, account) in refund_ix.accounts.iter().enumerate() {
         println!("Account {}: {}", index, account.pubkey);
     }
     send_transaction(&mut ctx.svm, refund_ix, &[&ctx.donar], &contributor_pubkey);
 }
+
+pub fn try_refund_transaction(ctx: &mut TestContext) -> TransactionResult {
+    let refund_ix = refund_ix(ctx);
+    let contributor_pubkey = ctx.donar.pubkey();
+    try_send_transaction(&mut ctx.svm, refund_ix, &[&ctx.donar], &contributor_pubkey)
+}
+
+pub fn withdraw_ix(ctx: &TestContext, amount: u64) -> Instruction {
+    client::withdraw(
+        &ctx.donar.pubkey(),
+        &ctx.maker.pubkey(),
+        ctx.campaign_id,
+        &ctx.mint,
+        &ctx.token_program,
+        ctx.vault_kind,
+        amount,
+    )
+}
+
+pub fn send_withdraw_transaction(ctx: &mut TestContext, amount: u64) {
+    let withdraw_ix = withdraw_ix(ctx, amount);
+    let contributor_pubkey = ctx.donar.pubkey();
+    send_transaction(
+        &mut ctx.svm,
+        withdraw_ix,
+        &[&ctx.donar],
+        &contributor_pubkey,
+    );
+}
+
+pub fn try_withdraw_transaction(ctx: &mut TestContext, amount: u64) -> TransactionResult {
+    let withdraw_ix = withdraw_ix(ctx, amount);
+    let contributor_pubkey = ctx.donar.pubkey();
+    try_send_transaction(
+        &mut ctx.svm,
+        withdraw_ix,
+        &[&ctx.donar],
+        &contributor_pubkey,
+    )
+}
+
+pub fn refund_batch_ix(ctx: &TestContext, payer: &Pubkey, contributors: &[Pubkey]) -> Instruction {
+    client::refund_batch(
+        payer,
+        &ctx.maker.pubkey(),
+        ctx.campaign_id,
+        &ctx.mint,
+        &ctx.token_program,
+        ctx.vault_kind,
+        contributors,
+    )
+}
+
+pub fn close_contribution_ix(ctx: &TestContext) -> Instruction {
+    client::close_contribution(&ctx.donar.pubkey(), &ctx.maker.pubkey(), ctx.campaign_id)
+}
+
+pub fn send_close_contribution_transaction(ctx: &mut TestContext) {
+    let close_contribution_ix = close_contribution_ix(ctx);
+    let contributor_pubkey = ctx.donar.pubkey();
+    send_transaction(
+        &mut ctx.svm,
+        close_contribution_ix,
+        &[&ctx.donar],
+        &contributor_pubkey,
+    );
+}
+
+pub fn try_close_contribution_transaction(ctx: &mut TestContext) -> TransactionResult {
+    let close_contribution_ix = close_contribution_ix(ctx);
+    let contributor_pubkey = ctx.donar.pubkey();
+    try_send_transaction(
+        &mut ctx.svm,
+        close_contribution_ix,
+        &[&ctx.donar],
+        &contributor_pubkey,
+    )
+}
@@ -1,6 +1,6 @@
-pub mod setup;
 pub mod fixtures;
 pub mod instructions;
+pub mod setup;
 pub mod utils;
 
-pub use setup::*;
\ No newline at end of file
+pub use setup::*;
@@ -3,15 +3,32 @@ use std::path::PathBuf;
 use litesvm::LiteSVM;
 use litesvm_token::{
     CreateAssociatedTokenAccount, CreateMint, MintTo,
+    spl_token::{
+        extension::{
+            ExtensionType, transfer_fee::instruction::initialize_transfer_fee_config, transfer_hook,
+        },
+        instruction::initialize_mint2,
+        state::Mint,
+    },
 };
 use solana_sdk::{
-    native_token::LAMPORTS_PER_SOL,
-    pubkey::Pubkey,
-    signature::Keypair,
-    signer::Signer,
+    account::Account, message::Instruction, native_token::LAMPORTS_PER_SOL, program_pack::Pack,
+    pubkey::Pubkey, signature::Keypair, signer::Signer, transaction::Transaction,
 };
+use solana_system_interface::instruction::create_account;
 
-use crate::fixtures::{AMOUNT_TO_RAISE, ASSOCIATED_TOKEN_PROGRAM_ID, program_id};
+use pinocchio_fundraiser::{
+    client,
+    instructions::ContributionLimits,
+    state::{FundingMode, VaultKind, WithdrawalPolicy},
+    token::{NATIVE_MINT, TOKEN_2022_PROGRAM_ID},
+    vault::NATIVE_SOL,
+};
+
+use crate::fixtures::{
+    AMOUNT_TO_RAISE, ASSOCIATED_TOKEN_PROGRAM_ID, CAMPAIGN_ID, CONTRIBUTION_LIMITS, HARD_CAP,
+    TOKEN_PROGRAM_ID, program_id,
+};
 
 pub struct TestContext {
     pub svm: LiteSVM,
@@ -20,16 +37,177 @@ pub struct TestContext {
     pub mint: Pubkey,
     pub maker_ata: Pubkey,
     pub donar_ata: Pubkey,
+    pub campaign_id: u64,
+    /// Campaign settings used by the initialize helpers.
+    pub hard_cap: u64,
+    pub limits: ContributionLimits,
+    pub funding_mode: FundingMode,
+    pub early_checkout: bool,
+    pub withdrawal_policy: WithdrawalPolicy,
+    pub vault_kind: VaultKind,
     pub fundraiser: Pubkey,
     pub contribution: Pubkey,
     pub fundraiser_bump: u8,
     pub contribution_bump: u8,
     pub vault_ata: Pubkey,
+    /// Owner of `mint`: SPL Token or Token-2022.
+    pub token_program: Pubkey,
     pub associated_token_program: Pubkey,
     pub system_program: Pubkey,
 }
 
 pub fn setup() -> TestContext {
+    setup_with_token_program(TOKEN_PROGRAM_ID)
+}
+
+/// Like [`setup`], with the mint and token accounts owned by `token_program`.
+pub fn setup_with_token_program(token_program: Pubkey) -> TestContext {
+    setup_with_mint(token_program, |svm, payer, authority| {
+        CreateMint::new(svm, payer)
+            .decimals(6)
+            .authority(authority)
+            .token_program_id(&token_program)
+            .send()
+            .unwrap()
+    })
+}
+
+/// Like [`setup`], with a Token-2022 mint that charges `basis_points` on every transfer.
+pub fn setup_with_transfer_fee(basis_points: u16) -> TestContext {
+    setup_with_mint(TOKEN_2022_PROGRAM_ID, |svm, payer, authority| {
+        create_extended_mint(
+            svm,
+            payer,
+            authority,
+            ExtensionType::TransferFeeConfig,
+            |mint| {
+                initialize_transfer_fee_config(
+                    &TOKEN_2022_PROGRAM_ID,
+                    mint,
+                    Some(authority),
+                    Some(authority),
+                    basis_points,
+                    u64::MAX,
+                )
+                .unwrap()
+            },
+        )
+    })
+}
+
+/// Like [`setup`], with a Token-2022 mint whose transfers call a hook program.
+pub fn setup_with_transfer_hook() -> TestContext {
+    setup_with_mint(TOKEN_2022_PROGRAM_ID, |svm, payer, authority| {
+        create_extended_mint(svm, payer, authority, ExtensionType::TransferHook, |mint| {
+            transfer_hook::instruction::initialize(
+                &TOKEN_2022_PROGRAM_ID,
+                mint,
+                Some(*authority),
+                Some(Pubkey::new_unique()),
+            )
+            .unwrap()
+        })
+    })
+}
+
+/// Creates a Token-2022 mint with one extension, initialized by the instruction that
+/// `init_extension` builds for the mint's address.
+fn create_extended_mint(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    authority: &Pubkey,
+    extension: ExtensionType,
+    init_extension: impl FnOnce(&Pubkey) -> Instruction,
+) -> Pubkey {
+    let mint = Keypair::new();
+    let mint_len = ExtensionType::try_calculate_account_len::<Mint>(&[extension]).unwrap();
+    let instructions = [
+        create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            svm.minimum_balance_for_rent_exemption(mint_len),
+            mint_len as u64,
+            &TOKEN_2022_PROGRAM_ID,
+        ),
+        init_extension(&mint.pubkey()),
+        initialize_mint2(&TOKEN_2022_PROGRAM_ID, &mint.pubkey(), authority, None, 6).unwrap(),
+    ];
+    let tx = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&payer.pubkey()),
+        &[payer, &mint],
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx)
+        .expect("Failed to create extended mint");
+    mint.pubkey()
+}
+
+/// Like [`setup`], for a native-SOL campaign: the wallets stand in for their token accounts
+/// and the vault is the fundraiser's lamport vault.
+pub fn setup_native_sol() -> TestContext {
+    let mut ctx = with_pda_vault(setup());
+    ctx.mint = NATIVE_SOL;
+    ctx.token_program = NATIVE_SOL;
+    ctx.maker_ata = ctx.maker.pubkey();
+    ctx.donar_ata = ctx.donar.pubkey();
+    ctx
+}
+
+/// Switches `ctx` to a campaign whose vault is the `[b"vault", fundraiser]` PDA.
+pub fn with_pda_vault(mut ctx: TestContext) -> TestContext {
+    ctx.vault_kind = VaultKind::Pda;
+    ctx.vault_ata = client::find_vault_address(&ctx.fundraiser).0;
+    ctx
+}
+
+/// Like [`setup`], for a wrapped SOL campaign. Only the maker has a wrapped SOL account;
+/// contributions wrap the donar's lamports.
+pub fn setup_wrapped_sol() -> TestContext {
+    let mut ctx = setup();
+    let mut data = vec![0; Mint::LEN];
+    Mint {
+        decimals: 9,
+        is_initialized: true,
+        ..Mint::default()
+    }
+    .pack_into_slice(&mut data);
+    ctx.svm
+        .set_account(
+            NATIVE_MINT,
+            Account {
+                lamports: ctx.svm.minimum_balance_for_rent_exemption(Mint::LEN),
+                data,
+                owner: TOKEN_PROGRAM_ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+    ctx.mint = NATIVE_MINT;
+    ctx.maker_ata = CreateAssociatedTokenAccount::new(&mut ctx.svm, &ctx.maker, &NATIVE_MINT)
+        .owner(&ctx.maker.pubkey())
+        .token_program_id(&TOKEN_PROGRAM_ID)
+        .send()
+        .unwrap();
+    ctx.donar_ata =
+        client::get_associated_token_address(&ctx.donar.pubkey(), &NATIVE_MINT, &TOKEN_PROGRAM_ID);
+    ctx.vault_ata = client::get_vault_address(
+        &ctx.fundraiser,
+        &NATIVE_MINT,
+        &TOKEN_PROGRAM_ID,
+        ctx.vault_kind,
+    );
+    ctx
+}
+
+/// Builds the test context around the mint returned by `create_mint`, which gets the maker as
+/// payer and the donar as mint authority.
+fn setup_with_mint(
+    token_program: Pubkey,
+    create_mint: impl FnOnce(&mut LiteSVM, &Keypair, &Pubkey) -> Pubkey,
+) -> TestContext {
     let mut svm = LiteSVM::new();
     let maker = Keypair::new();
     let donar = Keypair::new();
@@ -40,54 +218,60 @@ pub fn setup() -> TestContext {
         .expect("Airdrop failed for donar");
 
     // Load program SO file
-    let so_path = PathBuf::from(
-"target/sbpf-solana-solana/release/pinocchio_fundraiser.so"
-    );
+    let so_path = PathBuf::from("target/sbpf-solana-solana/release/pinocchio_fundraiser.so");
     let program_data = std::fs::read(so_path).expect("Failed to read program SO file");
     svm.add_program(program_id(), &program_data)
         .expect("Failed to add program");
 
     // Create mints
-    let mint = CreateMint::new(&mut svm, &maker)
-        .decimals(6)
-        .authority(&donar.pubkey())
-        .send()
-        .unwrap();
+    let mint = create_mint(&mut svm, &maker, &donar.pubkey());
 
     let donar_ata = CreateAssociatedTokenAccount::new(&mut svm, &donar, &mint)
         .owner(&donar.pubkey())
+        .token_program_id(&token_program)
         .send()
         .unwrap();
 
     let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, &donar, &mint)
         .owner(&maker.pubkey())
+        .token_program_id(&token_program)
         .send()
         .unwrap();
 
-
     // Derive fundraise PDA and vault
     let (fundraiser, fundraiser_bump) = Pubkey::find_program_address(
-        &[b"fundraiser".as_ref(), maker.pubkey().as_ref()],
+        &[
+            b"fundraiser".as_ref(),
+            maker.pubkey().as_ref(),
+            &CAMPAIGN_ID.to_le_bytes(),
+        ],
         &program_id(),
     );
 
     // Derive contribution PDA
     let (contribution, contribution_bump) = Pubkey::find_program_address(
-        &[b"contributor".as_ref(), fundraiser.as_ref(), donar.pubkey().as_ref()],
+        &[
+            b"contributor".as_ref(),
+            fundraiser.as_ref(),
+            donar.pubkey().as_ref(),
+        ],
         &program_id(),
     );
 
-
-
-    let vault_ata = spl_associated_token_account::get_associated_token_address(&fundraiser, &mint);
+    let vault_ata = spl_associated_token_account::get_associated_token_address_with_program_id(
+        &fundraiser,
+        &mint,
+        &token_program,
+    );
 
     // Mint tokens to donar
     MintTo::new(&mut svm, &donar, &mint, &donar_ata, 2 * AMOUNT_TO_RAISE)
+        .token_program_id(&token_program)
         .send()
         .unwrap();
 
     let associated_token_program = ASSOCIATED_TOKEN_PROGRAM_ID.parse::<Pubkey>().unwrap();
-    let system_program = Pubkey::from(pinocchio_system::ID);
+    let system_program = pinocchio_system::ID;
 
     TestContext {
         svm,
@@ -96,13 +280,20 @@ pub fn setup() -> TestContext {
         mint,
         donar_ata,
         maker_ata,
+        campaign_id: CAMPAIGN_ID,
+        hard_cap: HARD_CAP,
+        limits: CONTRIBUTION_LIMITS,
+        funding_mode: FundingMode::AllOrNothing,
+        early_checkout: false,
+        withdrawal_policy: WithdrawalPolicy::Disabled,
+        vault_kind: VaultKind::AssociatedToken,
         fundraiser,
         contribution,
         fundraiser_bump,
         contribution_bump,
         vault_ata,
+        token_program,
         associated_token_program,
         system_program,
     }
 }
-
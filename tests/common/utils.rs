@@ -1,10 +1,12 @@
-use litesvm::LiteSVM;
+use litesvm::{LiteSVM, types::TransactionResult};
+use pinocchio_fundraiser::error::FundraiserError;
 use solana_sdk::{
     clock::Clock,
+    instruction::InstructionError,
     message::{Instruction, Message},
     pubkey::Pubkey,
     signature::Keypair,
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
 };
 
 pub fn set_clock(svm: &mut LiteSVM, unix_timestamp: i64) {
@@ -15,13 +17,30 @@ pub fn set_clock(svm: &mut LiteSVM, unix_timestamp: i64) {
     svm.set_sysvar(&clock);
 }
 
-pub fn send_transaction(svm: &mut LiteSVM, ix: Instruction, signers: &[&Keypair], payer: &Pubkey) {
+pub fn try_send_transaction(
+    svm: &mut LiteSVM,
+    ix: Instruction,
+    signers: &[&Keypair],
+    payer: &Pubkey,
+) -> TransactionResult {
     let message = Message::new(&[ix], Some(payer));
     let recent_blockhash = svm.latest_blockhash();
     let transaction = Transaction::new(signers, message, recent_blockhash);
-    let tx = svm
-        .send_transaction(transaction)
-        .expect("Transaction should succeed");
-    println!("{}",tx.pretty_logs());
+    svm.send_transaction(transaction)
+}
+
+pub fn send_transaction(svm: &mut LiteSVM, ix: Instruction, signers: &[&Keypair], payer: &Pubkey) {
+    let tx = try_send_transaction(svm, ix, signers, payer).expect("Transaction should succeed");
+    println!("{}", tx.pretty_logs());
     println!("CUs Consumed: {}", tx.compute_units_consumed);
 }
+
+pub fn assert_instruction_error(result: TransactionResult, expected: InstructionError) {
+    let failed = result.expect_err("Transaction should fail");
+    println!("{}", failed.meta.pretty_logs());
+    assert_eq!(failed.err, TransactionError::InstructionError(0, expected));
+}
+
+pub fn assert_fundraiser_error(result: TransactionResult, expected: FundraiserError) {
+    assert_instruction_error(result, InstructionError::Custom(expected as u32));
+}
@@ -1,20 +1,47 @@
-use litesvm_token::{get_spl_account, spl_token::state::Account};
-use pinocchio_fundraiser::state::{Contribution, Fundraiser};
-use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use litesvm_token::{
+    CreateAccount, CreateAssociatedTokenAccount, CreateMint, MintTo, get_spl_account,
+    spl_token::state::Account,
+};
+use pinocchio_fundraiser::{
+    client,
+    constants::MAKER_SEED,
+    error::FundraiserError,
+    instructions::{ContributionCap, ContributionLimits, FundraiserInstruction, InitializeData},
+    state::{
+        Contribution, FundingMode, Fundraiser, FundraiserStatus, Maker, VaultKind, WithdrawalPolicy,
+    },
+    token::TOKEN_2022_PROGRAM_ID,
+};
+use solana_sdk::{
+    instruction::InstructionError, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey,
+    signature::Keypair, signer::Signer,
+};
 
 use crate::{
-    fixtures::{AMOUNT_TO_RAISE, DONATION_AMOUNT, DURATION_IN_DAYS},
+    TestContext,
+    fixtures::{AMOUNT_TO_RAISE, CONTRIBUTION_LIMITS, DONATION_AMOUNT, END_TS, START_TS},
     instructions::{
-        send_checkout_transaction, send_contribution_transaction, send_initialize_transaction,
-        send_refund_transaction,
+        cancel_ix, checkout_ix, close_contribution_ix, contribution_ix, initialize_ix,
+        refund_batch_ix, refund_ix, send_cancel_transaction, send_checkout_transaction,
+        send_close_contribution_transaction, send_contribution_transaction,
+        send_initialize_transaction, send_refund_transaction, send_withdraw_transaction,
+        try_cancel_transaction, try_checkout_transaction, try_close_contribution_transaction,
+        try_contribution_transaction, try_initialize_transaction, try_refund_transaction,
+        try_withdraw_transaction,
     },
-    setup,
-    utils::set_clock,
+    setup, setup_native_sol, setup_with_token_program, setup_with_transfer_fee,
+    setup_with_transfer_hook, setup_wrapped_sol,
+    utils::{
+        assert_fundraiser_error, assert_instruction_error, send_transaction, set_clock,
+        try_send_transaction,
+    },
+    with_pda_vault,
 };
 
 #[test]
 pub fn test_init_inx() {
     let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
     send_initialize_transaction(&mut ctx);
     let pda = ctx
         .svm
@@ -31,12 +58,31 @@ pub fn test_init_inx() {
         AMOUNT_TO_RAISE,
         u64::from_le_bytes(fundraise_pda.amount_to_raise)
     );
+    assert_eq!(START_TS, i64::from_le_bytes(fundraise_pda.start_ts));
+    assert_eq!(END_TS, i64::from_le_bytes(fundraise_pda.end_ts));
+    assert_eq!(fundraise_pda.discriminator, Fundraiser::DISCRIMINATOR as u8);
+    assert_eq!(fundraise_pda.version, Fundraiser::VERSION);
+    assert_eq!(fundraise_pda.status, FundraiserStatus::Active as u8);
+    assert_eq!(
+        CONTRIBUTION_LIMITS.min_contribution,
+        u64::from_le_bytes(fundraise_pda.min_contribution)
+    );
+    assert_eq!(
+        AMOUNT_TO_RAISE,
+        u64::from_le_bytes(fundraise_pda.max_contribution)
+    );
+    assert_eq!(
+        CONTRIBUTION_LIMITS.max_contributors,
+        u32::from_le_bytes(fundraise_pda.max_contributors)
+    );
+    assert_eq!(0, u32::from_le_bytes(fundraise_pda.contributor_count));
+    assert_eq!(fundraise_pda.funding_mode, FundingMode::AllOrNothing as u8);
 }
 
 #[test]
 pub fn test_contribution_inx() {
     let mut ctx = setup();
-    set_clock(&mut ctx.svm, 1000);
+    set_clock(&mut ctx.svm, START_TS);
     send_initialize_transaction(&mut ctx);
     send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
     let pda = ctx
@@ -45,29 +91,59 @@ pub fn test_contribution_inx() {
         .expect("account not found");
     let contributor_data =
         ::wincode::deserialize::<Contribution>(&pda.data).expect("unable to deserialize");
-    assert_eq!(contributor_data.amount, DONATION_AMOUNT);
+    assert_eq!(u64::from_le_bytes(contributor_data.amount), DONATION_AMOUNT);
+    assert_eq!(
+        contributor_data.discriminator,
+        Contribution::DISCRIMINATOR as u8
+    );
+    assert_eq!(contributor_data.version, Contribution::VERSION);
 }
 
 #[test]
 pub fn test_checkout_inx() {
     let mut ctx = setup();
-    set_clock(&mut ctx.svm, 1000);
+    set_clock(&mut ctx.svm, START_TS);
     send_initialize_transaction(&mut ctx);
     send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
-    set_clock(&mut ctx.svm, DURATION_IN_DAYS as i64 * 86_400 * 2);
+    set_clock(&mut ctx.svm, END_TS);
     send_checkout_transaction(&mut ctx);
     let maker_ata: Account =
         get_spl_account(&ctx.svm, &ctx.maker_ata).expect("token account not found");
     assert_eq!(maker_ata.amount, AMOUNT_TO_RAISE);
+    assert_eq!(ctx.svm.get_balance(&ctx.fundraiser).unwrap_or(0), 0);
+}
+
+#[test]
+pub fn test_checkout_inx_returns_fundraiser_rent_to_maker() {
+    let mut ctx = funded_checkout_context();
+    let fundraiser_rent = ctx.svm.get_balance(&ctx.fundraiser).unwrap();
+    let vault_rent = ctx.svm.get_balance(&ctx.vault_ata).unwrap();
+    let maker_before = ctx.svm.get_balance(&ctx.maker.pubkey()).unwrap();
+    send_checkout_transaction(&mut ctx);
+    let fee = 5_000;
+    assert_eq!(
+        ctx.svm.get_balance(&ctx.maker.pubkey()).unwrap(),
+        maker_before + fundraiser_rent + vault_rent - fee
+    );
+}
+
+#[test]
+pub fn test_checkout_inx_fails_if_already_withdrawn() {
+    let mut ctx = funded_checkout_context();
+    send_checkout_transaction(&mut ctx);
+    ctx.svm.expire_blockhash();
+    // the fundraiser account is closed by the first checkout
+    let result = try_checkout_transaction(&mut ctx);
+    assert_instruction_error(result, InstructionError::InvalidAccountOwner);
 }
 
 #[test]
 pub fn test_refund_inx() {
     let mut ctx = setup();
-    set_clock(&mut ctx.svm, 1000);
+    set_clock(&mut ctx.svm, START_TS);
     send_initialize_transaction(&mut ctx);
     send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
-    set_clock(&mut ctx.svm, DURATION_IN_DAYS as i64 * 86_400 * 2);
+    set_clock(&mut ctx.svm, END_TS);
     let donar_ata_before: Account =
         get_spl_account(&ctx.svm, &ctx.donar_ata).expect("token account not found");
     send_refund_transaction(&mut ctx);
@@ -76,29 +152,1780 @@ pub fn test_refund_inx() {
     assert!(donar_ata_after.amount > donar_ata_before.amount)
 }
 
-#[should_panic]
 #[test]
-pub fn test_refund_inx_fails_if_amount_reached() {
+pub fn test_cancel_inx() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    send_cancel_transaction(&mut ctx);
+    let pda = ctx.svm.get_account(&ctx.fundraiser).unwrap();
+    let fundraise_pda =
+        ::wincode::deserialize::<Fundraiser>(&pda.data).expect("unable to deserialize ");
+    assert_eq!(fundraise_pda.status, FundraiserStatus::Cancelled as u8);
+}
+
+#[test]
+pub fn test_cancel_inx_closes_the_campaign_if_nothing_raised() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    let rent = ctx.svm.get_balance(&ctx.fundraiser).unwrap()
+        + ctx.svm.get_balance(&ctx.vault_ata).unwrap();
+    let maker_before = ctx.svm.get_balance(&ctx.maker.pubkey()).unwrap();
+    send_cancel_transaction(&mut ctx);
+    assert_eq!(ctx.svm.get_balance(&ctx.fundraiser).unwrap_or(0), 0);
+    assert_eq!(ctx.svm.get_balance(&ctx.vault_ata).unwrap_or(0), 0);
+    assert_eq!(
+        ctx.svm.get_balance(&ctx.maker.pubkey()).unwrap(),
+        maker_before + rent - 5_000
+    );
+}
+
+#[test]
+pub fn test_cancel_inx_closes_the_campaign_once_everything_is_withdrawn() {
     let mut ctx = setup();
-    set_clock(&mut ctx.svm, 1000);
+    ctx.withdrawal_policy = WithdrawalPolicy::Anytime;
+    set_clock(&mut ctx.svm, START_TS);
     send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    send_withdraw_transaction(&mut ctx, DONATION_AMOUNT);
+    send_cancel_transaction(&mut ctx);
+    assert_eq!(ctx.svm.get_balance(&ctx.fundraiser).unwrap_or(0), 0);
+    assert_eq!(ctx.svm.get_balance(&ctx.vault_ata).unwrap_or(0), 0);
+}
+
+#[test]
+pub fn test_refund_inx_succeeds_right_after_cancel() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    // the goal is reached and the deadline is far away, neither matters once cancelled
     send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
-    set_clock(&mut ctx.svm, DURATION_IN_DAYS as i64 * 86_400 * 2);
+    send_cancel_transaction(&mut ctx);
     let donar_ata_before: Account =
         get_spl_account(&ctx.svm, &ctx.donar_ata).expect("token account not found");
     send_refund_transaction(&mut ctx);
     let donar_ata_after: Account =
         get_spl_account(&ctx.svm, &ctx.donar_ata).expect("token account not found");
-    assert!(donar_ata_after.amount > donar_ata_before.amount)
+    assert_eq!(
+        donar_ata_after.amount,
+        donar_ata_before.amount + AMOUNT_TO_RAISE
+    );
 }
 
-#[should_panic]
 #[test]
-pub fn test_checkout_inx_fails_if_amount_not_raised() {
+pub fn test_contribution_inx_fails_if_cancelled() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    send_cancel_transaction(&mut ctx);
+    ctx.svm.expire_blockhash();
+    let result = try_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    assert_fundraiser_error(result, FundraiserError::FundraiserCancelled);
+}
+
+#[test]
+pub fn test_checkout_inx_fails_if_cancelled() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    send_cancel_transaction(&mut ctx);
+    set_clock(&mut ctx.svm, END_TS);
+    let result = try_checkout_transaction(&mut ctx);
+    assert_fundraiser_error(result, FundraiserError::FundraiserCancelled);
+}
+
+#[test]
+pub fn test_cancel_inx_fails_if_already_cancelled() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    send_cancel_transaction(&mut ctx);
+    ctx.svm.expire_blockhash();
+    let result = try_cancel_transaction(&mut ctx);
+    assert_fundraiser_error(result, FundraiserError::FundraiserCancelled);
+}
+
+#[test]
+pub fn test_cancel_inx_fails_if_ended() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    set_clock(&mut ctx.svm, END_TS);
+    let result = try_cancel_transaction(&mut ctx);
+    assert_fundraiser_error(result, FundraiserError::FundraiserEnded);
+}
+
+#[test]
+pub fn test_cancel_inx_fails_if_signer_is_not_maker() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    let attacker = funded_keypair(&mut ctx);
+    let mut ix = cancel_ix(&ctx);
+    ix.accounts[0].pubkey = attacker.pubkey();
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&attacker], &attacker.pubkey());
+    assert_fundraiser_error(result, FundraiserError::MakerMismatch);
+}
+
+#[test]
+pub fn test_all_or_nothing_refunds_when_goal_missed() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    set_clock(&mut ctx.svm, END_TS);
+    let result = try_checkout_transaction(&mut ctx);
+    assert_fundraiser_error(result, FundraiserError::GoalNotReached);
+    send_refund_transaction(&mut ctx);
+    let donar_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.donar_ata).expect("token account not found");
+    assert_eq!(donar_ata.amount, 2 * AMOUNT_TO_RAISE);
+}
+
+#[test]
+pub fn test_keep_what_you_raise_checks_out_below_goal() {
+    let mut ctx = setup();
+    ctx.funding_mode = FundingMode::KeepWhatYouRaise;
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+
+    set_clock(&mut ctx.svm, END_TS - 1);
+    let result = try_checkout_transaction(&mut ctx);
+    assert_fundraiser_error(result, FundraiserError::FundraiserNotEnded);
+
+    set_clock(&mut ctx.svm, END_TS);
+    let result = try_refund_transaction(&mut ctx);
+    assert_fundraiser_error(result, FundraiserError::GoalReached);
+    send_checkout_transaction(&mut ctx);
+    let maker_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.maker_ata).expect("token account not found");
+    assert_eq!(maker_ata.amount, DONATION_AMOUNT);
+}
+
+#[test]
+pub fn test_keep_what_you_raise_checkout_closes_the_campaign_if_nothing_raised() {
+    let mut ctx = setup();
+    ctx.funding_mode = FundingMode::KeepWhatYouRaise;
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    set_clock(&mut ctx.svm, END_TS);
+    send_checkout_transaction(&mut ctx);
+    let maker_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.maker_ata).expect("token account not found");
+    assert_eq!(maker_ata.amount, 0);
+    assert_eq!(ctx.svm.get_balance(&ctx.vault_ata).unwrap_or(0), 0);
+    assert_eq!(ctx.svm.get_balance(&ctx.fundraiser).unwrap_or(0), 0);
+}
+
+#[test]
+pub fn test_all_or_nothing_checkout_closes_the_campaign_if_nothing_raised() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    set_clock(&mut ctx.svm, END_TS);
+    let rent = ctx.svm.get_balance(&ctx.fundraiser).unwrap()
+        + ctx.svm.get_balance(&ctx.vault_ata).unwrap();
+    let maker_before = ctx.svm.get_balance(&ctx.maker.pubkey()).unwrap();
+    send_checkout_transaction(&mut ctx);
+    assert_eq!(ctx.svm.get_balance(&ctx.vault_ata).unwrap_or(0), 0);
+    assert_eq!(ctx.svm.get_balance(&ctx.fundraiser).unwrap_or(0), 0);
+    assert_eq!(
+        ctx.svm.get_balance(&ctx.maker.pubkey()).unwrap(),
+        maker_before + rent - 5_000
+    );
+}
+
+#[test]
+pub fn test_keep_what_you_raise_refunds_after_cancel() {
+    let mut ctx = setup();
+    ctx.funding_mode = FundingMode::KeepWhatYouRaise;
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    send_cancel_transaction(&mut ctx);
+    send_refund_transaction(&mut ctx);
+    let donar_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.donar_ata).expect("token account not found");
+    assert_eq!(donar_ata.amount, 2 * AMOUNT_TO_RAISE);
+}
+
+#[test]
+pub fn test_early_checkout_once_goal_reached() {
     let mut ctx = setup();
-    set_clock(&mut ctx.svm, 1000);
+    ctx.early_checkout = true;
+    set_clock(&mut ctx.svm, START_TS);
     send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE - DONATION_AMOUNT);
+    let result = try_checkout_transaction(&mut ctx);
+    assert_fundraiser_error(result, FundraiserError::GoalNotReached);
+
     send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
-    set_clock(&mut ctx.svm, DURATION_IN_DAYS as i64 * 86_400 * 2);
+    ctx.svm.expire_blockhash();
     send_checkout_transaction(&mut ctx);
+    let maker_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.maker_ata).expect("token account not found");
+    assert_eq!(maker_ata.amount, AMOUNT_TO_RAISE);
+    assert_eq!(ctx.svm.get_balance(&ctx.fundraiser).unwrap_or(0), 0);
+
+    let result = try_contribution_transaction(&mut ctx, 2 * DONATION_AMOUNT);
+    assert_instruction_error(result, InstructionError::InvalidAccountOwner);
+}
+
+#[test]
+pub fn test_early_checkout_is_opt_in() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    let result = try_checkout_transaction(&mut ctx);
+    assert_fundraiser_error(result, FundraiserError::FundraiserNotEnded);
+}
+
+#[test]
+pub fn test_withdraw_inx_partial() {
+    let mut ctx = setup();
+    ctx.withdrawal_policy = WithdrawalPolicy::Anytime;
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, 2 * DONATION_AMOUNT);
+    send_withdraw_transaction(&mut ctx, DONATION_AMOUNT);
+
+    let donar_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.donar_ata).expect("token account not found");
+    assert_eq!(donar_ata.amount, 2 * AMOUNT_TO_RAISE - DONATION_AMOUNT);
+    let pda = ctx.svm.get_account(&ctx.contribution).unwrap();
+    let contribution =
+        ::wincode::deserialize::<Contribution>(&pda.data).expect("unable to deserialize");
+    assert_eq!(DONATION_AMOUNT, u64::from_le_bytes(contribution.amount));
+    let pda = ctx.svm.get_account(&ctx.fundraiser).unwrap();
+    let fundraise_pda =
+        ::wincode::deserialize::<Fundraiser>(&pda.data).expect("unable to deserialize ");
+    assert_eq!(
+        DONATION_AMOUNT,
+        u64::from_le_bytes(fundraise_pda.current_amount)
+    );
+}
+
+#[test]
+pub fn test_withdraw_inx_full_closes_contribution() {
+    let mut ctx = setup();
+    ctx.withdrawal_policy = WithdrawalPolicy::Anytime;
+    ctx.limits.max_contributors = 1;
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    send_withdraw_transaction(&mut ctx, DONATION_AMOUNT);
+
+    assert!(
+        ctx.svm
+            .get_account(&ctx.contribution)
+            .is_none_or(|account| account.lamports == 0)
+    );
+    let pda = ctx.svm.get_account(&ctx.fundraiser).unwrap();
+    let fundraise_pda =
+        ::wincode::deserialize::<Fundraiser>(&pda.data).expect("unable to deserialize ");
+    assert_eq!(0, u64::from_le_bytes(fundraise_pda.current_amount));
+    assert_eq!(0, u32::from_le_bytes(fundraise_pda.contributor_count));
+
+    // the freed slot can be taken again
+    ctx.svm.expire_blockhash();
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+}
+
+#[test]
+pub fn test_withdraw_inx_fails_if_more_than_contributed() {
+    let mut ctx = setup();
+    ctx.withdrawal_policy = WithdrawalPolicy::Anytime;
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    let result = try_withdraw_transaction(&mut ctx, DONATION_AMOUNT + 1);
+    assert_instruction_error(result, InstructionError::InsufficientFunds);
+}
+
+#[test]
+pub fn test_withdraw_inx_fails_if_disabled() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    let result = try_withdraw_transaction(&mut ctx, DONATION_AMOUNT);
+    assert_fundraiser_error(result, FundraiserError::WithdrawalsDisabled);
+}
+
+#[test]
+pub fn test_withdraw_inx_within_cooling_off_period() {
+    let mut ctx = setup();
+    ctx.withdrawal_policy = WithdrawalPolicy::CoolingOff(3_600);
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, 2 * DONATION_AMOUNT);
+
+    set_clock(&mut ctx.svm, START_TS + 3_599);
+    send_withdraw_transaction(&mut ctx, DONATION_AMOUNT);
+
+    set_clock(&mut ctx.svm, START_TS + 3_600);
+    let result = try_withdraw_transaction(&mut ctx, DONATION_AMOUNT / 2);
+    assert_fundraiser_error(result, FundraiserError::CoolingOffExpired);
+}
+
+#[test]
+pub fn test_withdraw_inx_fails_if_ended() {
+    let mut ctx = setup();
+    ctx.withdrawal_policy = WithdrawalPolicy::Anytime;
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    set_clock(&mut ctx.svm, END_TS);
+    let result = try_withdraw_transaction(&mut ctx, DONATION_AMOUNT);
+    assert_fundraiser_error(result, FundraiserError::FundraiserEnded);
+}
+
+#[test]
+pub fn test_init_inx_fails_if_cooling_off_period_not_positive() {
+    let mut ctx = setup();
+    ctx.withdrawal_policy = WithdrawalPolicy::CoolingOff(0);
+    set_clock(&mut ctx.svm, START_TS);
+    let result = try_initialize_transaction(&mut ctx, AMOUNT_TO_RAISE, None, END_TS);
+    assert_fundraiser_error(result, FundraiserError::InvalidTimestamp);
+}
+
+#[test]
+pub fn test_init_inx_fails_if_amount_too_low() {
+    let mut ctx = setup();
+    let result = try_initialize_transaction(&mut ctx, 500, None, END_TS);
+    assert_fundraiser_error(result, FundraiserError::AmountToRaiseTooLow);
+}
+
+#[test]
+pub fn test_init_inx_fails_if_end_ts_not_in_future() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    let result = try_initialize_transaction(&mut ctx, AMOUNT_TO_RAISE, None, START_TS);
+    assert_fundraiser_error(result, FundraiserError::InvalidTimestamp);
+}
+
+#[test]
+pub fn test_init_inx_fails_if_start_ts_in_past() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    let result = try_initialize_transaction(&mut ctx, AMOUNT_TO_RAISE, Some(START_TS - 1), END_TS);
+    assert_fundraiser_error(result, FundraiserError::InvalidTimestamp);
+}
+
+#[test]
+pub fn test_init_inx_fails_if_start_ts_not_before_end_ts() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    let result = try_initialize_transaction(&mut ctx, AMOUNT_TO_RAISE, Some(END_TS), END_TS);
+    assert_fundraiser_error(result, FundraiserError::InvalidTimestamp);
+}
+
+#[test]
+pub fn test_scheduled_fundraiser_window() {
+    let mut ctx = setup();
+    let launch_ts = START_TS + 86_400;
+    set_clock(&mut ctx.svm, START_TS);
+    let result = try_initialize_transaction(&mut ctx, AMOUNT_TO_RAISE, Some(launch_ts), END_TS);
+    assert!(result.is_ok());
+
+    let pda = ctx.svm.get_account(&ctx.fundraiser).unwrap();
+    let fundraise_pda =
+        ::wincode::deserialize::<Fundraiser>(&pda.data).expect("unable to deserialize ");
+    assert_eq!(launch_ts, i64::from_le_bytes(fundraise_pda.start_ts));
+    assert_eq!(fundraise_pda.status, FundraiserStatus::Pending as u8);
+
+    // before the window
+    set_clock(&mut ctx.svm, launch_ts - 1);
+    let result = try_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    assert_fundraiser_error(result, FundraiserError::FundraiserNotStarted);
+
+    // during the window
+    set_clock(&mut ctx.svm, launch_ts);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+
+    // after the window
+    set_clock(&mut ctx.svm, END_TS);
+    let result = try_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    assert_fundraiser_error(result, FundraiserError::FundraiserEnded);
+}
+
+#[test]
+pub fn test_init_inx_fails_if_fundraiser_bump_is_wrong() {
+    let mut ctx = setup();
+    let mut init_ix = initialize_ix(&ctx, AMOUNT_TO_RAISE, None, END_TS);
+    init_ix.data = FundraiserInstruction::Initialize(InitializeData {
+        bump: ctx.fundraiser_bump.wrapping_sub(1),
+        campaign_id: ctx.campaign_id,
+        amount_to_raise: AMOUNT_TO_RAISE,
+        hard_cap: ctx.hard_cap,
+        start_ts: None,
+        end_ts: END_TS,
+        limits: ctx.limits,
+        funding_mode: ctx.funding_mode,
+        early_checkout: ctx.early_checkout,
+        withdrawal_policy: ctx.withdrawal_policy,
+        vault_kind: ctx.vault_kind,
+        vault_bump: client::find_campaign_vault_address(
+            &ctx.fundraiser,
+            &ctx.mint,
+            &ctx.token_program,
+            ctx.vault_kind,
+        )
+        .1,
+        maker_bump: client::find_maker_address(&ctx.maker.pubkey()).1,
+    })
+    .pack();
+    let maker_pubkey = ctx.maker.pubkey();
+    let result = try_send_transaction(&mut ctx.svm, init_ix, &[&ctx.maker], &maker_pubkey);
+    assert_fundraiser_error(result, FundraiserError::InvalidFundraiser);
+}
+
+#[test]
+pub fn test_contribution_inx_succeeds_until_end_ts() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    set_clock(&mut ctx.svm, END_TS - 1);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+}
+
+#[test]
+pub fn test_contribution_inx_fails_if_amount_too_small() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    let result = try_contribution_transaction(&mut ctx, 1);
+    assert_fundraiser_error(result, FundraiserError::ContributionTooSmall);
+}
+
+#[test]
+pub fn test_contribution_inx_fails_if_duration_over() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    set_clock(&mut ctx.svm, END_TS);
+    let result = try_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    assert_fundraiser_error(result, FundraiserError::FundraiserEnded);
+}
+
+#[test]
+pub fn test_contribution_inx_fails_if_limit_exceeded() {
+    let mut ctx = setup();
+    ctx.limits.max_per_contributor = ContributionCap::BasisPoints(1_000);
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE / 10);
+    let result = try_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    assert_fundraiser_error(result, FundraiserError::ContributionLimitExceeded);
+}
+
+#[test]
+pub fn test_contribution_inx_fails_if_first_contribution_exceeds_limit() {
+    let mut ctx = setup();
+    ctx.limits.max_per_contributor = ContributionCap::Amount(DONATION_AMOUNT);
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    let result = try_contribution_transaction(&mut ctx, DONATION_AMOUNT + 1);
+    assert_fundraiser_error(result, FundraiserError::ContributionLimitExceeded);
+}
+
+#[test]
+pub fn test_contribution_inx_applies_limit_to_new_and_existing_contributions() {
+    let cap = 2 * DONATION_AMOUNT;
+
+    // new contribution account: the whole cap at once
+    let mut ctx = setup();
+    ctx.limits.max_per_contributor = ContributionCap::Amount(cap);
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, cap);
+    let result = try_contribution_transaction(&mut ctx, CONTRIBUTION_LIMITS.min_contribution);
+    assert_fundraiser_error(result, FundraiserError::ContributionLimitExceeded);
+
+    // existing contribution account: the same cap across top-ups
+    let mut ctx = setup();
+    ctx.limits.max_per_contributor = ContributionCap::Amount(cap);
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    let result = try_contribution_transaction(&mut ctx, DONATION_AMOUNT + 1);
+    assert_fundraiser_error(result, FundraiserError::ContributionLimitExceeded);
+    ctx.svm.expire_blockhash();
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+
+    let pda = ctx.svm.get_account(&ctx.contribution).unwrap();
+    let contribution =
+        ::wincode::deserialize::<Contribution>(&pda.data).expect("unable to deserialize");
+    assert_eq!(cap, u64::from_le_bytes(contribution.amount));
+    let pda = ctx.svm.get_account(&ctx.fundraiser).unwrap();
+    let fundraise_pda =
+        ::wincode::deserialize::<Fundraiser>(&pda.data).expect("unable to deserialize ");
+    assert_eq!(1, u32::from_le_bytes(fundraise_pda.contributor_count));
+}
+
+#[test]
+pub fn test_contribution_inx_trims_to_hard_cap() {
+    let mut ctx = setup();
+    ctx.hard_cap = AMOUNT_TO_RAISE + DONATION_AMOUNT;
+    ctx.limits.max_per_contributor = ContributionCap::Amount(ctx.hard_cap);
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+
+    let donar_ata_before: Account =
+        get_spl_account(&ctx.svm, &ctx.donar_ata).expect("token account not found");
+    send_contribution_transaction(&mut ctx, 3 * DONATION_AMOUNT);
+    let donar_ata_after: Account =
+        get_spl_account(&ctx.svm, &ctx.donar_ata).expect("token account not found");
+    assert_eq!(
+        donar_ata_before.amount - donar_ata_after.amount,
+        DONATION_AMOUNT
+    );
+
+    let pda = ctx.svm.get_account(&ctx.fundraiser).unwrap();
+    let fundraise_pda =
+        ::wincode::deserialize::<Fundraiser>(&pda.data).expect("unable to deserialize ");
+    assert_eq!(
+        ctx.hard_cap,
+        u64::from_le_bytes(fundraise_pda.current_amount)
+    );
+    let pda = ctx.svm.get_account(&ctx.contribution).unwrap();
+    let contribution =
+        ::wincode::deserialize::<Contribution>(&pda.data).expect("unable to deserialize");
+    assert_eq!(ctx.hard_cap, u64::from_le_bytes(contribution.amount));
+
+    let result = try_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    assert_fundraiser_error(result, FundraiserError::HardCapReached);
+
+    // the maker checks out everything raised, not just the soft cap
+    set_clock(&mut ctx.svm, END_TS);
+    send_checkout_transaction(&mut ctx);
+    let maker_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.maker_ata).expect("token account not found");
+    assert_eq!(maker_ata.amount, ctx.hard_cap);
+}
+
+#[test]
+pub fn test_init_inx_fails_if_hard_cap_below_goal() {
+    let mut ctx = setup();
+    ctx.hard_cap = AMOUNT_TO_RAISE - 1;
+    set_clock(&mut ctx.svm, START_TS);
+    let result = try_initialize_transaction(&mut ctx, AMOUNT_TO_RAISE, None, END_TS);
+    assert_fundraiser_error(result, FundraiserError::InvalidHardCap);
+}
+
+#[test]
+pub fn test_contribution_inx_enforces_campaign_minimum() {
+    let mut ctx = setup();
+    ctx.limits.min_contribution = DONATION_AMOUNT;
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    let result = try_contribution_transaction(&mut ctx, DONATION_AMOUNT - 1);
+    assert_fundraiser_error(result, FundraiserError::ContributionTooSmall);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    // top-ups are held to the same minimum
+    ctx.svm.expire_blockhash();
+    let result = try_contribution_transaction(&mut ctx, DONATION_AMOUNT - 1);
+    assert_fundraiser_error(result, FundraiserError::ContributionTooSmall);
+}
+
+#[test]
+pub fn test_contribution_inx_fails_if_contributor_limit_reached() {
+    let mut ctx = setup();
+    ctx.limits.max_contributors = 1;
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    // existing contributors can still top up
+    send_contribution_transaction(&mut ctx, 2 * DONATION_AMOUNT);
+
+    let other = funded_contributor(&mut ctx);
+    let ix = client::contribute(
+        &other.pubkey(),
+        &ctx.maker.pubkey(),
+        ctx.campaign_id,
+        &ctx.mint,
+        &ctx.token_program,
+        ctx.vault_kind,
+        DONATION_AMOUNT,
+    );
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&other], &other.pubkey());
+    assert_fundraiser_error(result, FundraiserError::ContributorLimitReached);
+}
+
+#[test]
+pub fn test_init_inx_fails_if_contribution_limits_are_invalid() {
+    for limits in [
+        ContributionLimits {
+            min_contribution: 0,
+            ..CONTRIBUTION_LIMITS
+        },
+        ContributionLimits {
+            max_per_contributor: ContributionCap::BasisPoints(10_001),
+            ..CONTRIBUTION_LIMITS
+        },
+        ContributionLimits {
+            max_per_contributor: ContributionCap::Amount(CONTRIBUTION_LIMITS.min_contribution - 1),
+            ..CONTRIBUTION_LIMITS
+        },
+        ContributionLimits {
+            max_contributors: 0,
+            ..CONTRIBUTION_LIMITS
+        },
+    ] {
+        let mut ctx = setup();
+        ctx.limits = limits;
+        set_clock(&mut ctx.svm, START_TS);
+        let result = try_initialize_transaction(&mut ctx, AMOUNT_TO_RAISE, None, END_TS);
+        assert_fundraiser_error(result, FundraiserError::InvalidContributionLimits);
+    }
+}
+
+#[test]
+pub fn test_refund_inx_fails_if_amount_reached() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    set_clock(&mut ctx.svm, END_TS);
+    let result = try_refund_transaction(&mut ctx);
+    assert_fundraiser_error(result, FundraiserError::GoalReached);
+}
+
+#[test]
+pub fn test_refund_inx_fails_if_duration_not_over() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    let result = try_refund_transaction(&mut ctx);
+    assert_fundraiser_error(result, FundraiserError::FundraiserNotEnded);
+}
+
+#[test]
+pub fn test_checkout_inx_fails_if_amount_not_raised() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    set_clock(&mut ctx.svm, END_TS);
+    let result = try_checkout_transaction(&mut ctx);
+    assert_fundraiser_error(result, FundraiserError::GoalNotReached);
+}
+
+#[test]
+pub fn test_checkout_inx_fails_if_duration_not_over() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    set_clock(&mut ctx.svm, END_TS - 1);
+    let result = try_checkout_transaction(&mut ctx);
+    assert_fundraiser_error(result, FundraiserError::FundraiserNotEnded);
+}
+
+#[test]
+pub fn test_contribution_inx_fails_if_fundraiser_discriminator_is_wrong() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    let mut fundraiser = ctx.svm.get_account(&ctx.fundraiser).unwrap();
+    fundraiser.data[0] = Contribution::DISCRIMINATOR as u8;
+    ctx.svm.set_account(ctx.fundraiser, fundraiser).unwrap();
+    let result = try_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    assert_fundraiser_error(result, FundraiserError::InvalidAccountDiscriminator);
+}
+
+#[test]
+pub fn test_contribution_inx_fails_if_fundraiser_version_is_unknown() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    let mut fundraiser = ctx.svm.get_account(&ctx.fundraiser).unwrap();
+    fundraiser.data[1] = Fundraiser::VERSION + 1;
+    ctx.svm.set_account(ctx.fundraiser, fundraiser).unwrap();
+    let result = try_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    assert_fundraiser_error(result, FundraiserError::UnsupportedAccountVersion);
+}
+
+#[test]
+pub fn test_refund_inx_fails_if_contribution_discriminator_is_wrong() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    set_clock(&mut ctx.svm, END_TS);
+    let mut contribution = ctx.svm.get_account(&ctx.contribution).unwrap();
+    contribution.data[0] = Fundraiser::DISCRIMINATOR as u8;
+    ctx.svm.set_account(ctx.contribution, contribution).unwrap();
+    let result = try_refund_transaction(&mut ctx);
+    assert_fundraiser_error(result, FundraiserError::InvalidAccountDiscriminator);
+}
+
+#[test]
+pub fn test_maker_runs_concurrent_fundraisers() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+
+    let first_fundraiser = ctx.fundraiser;
+    ctx.campaign_id += 1;
+    (ctx.fundraiser, _) = client::find_fundraiser_address(&ctx.maker.pubkey(), ctx.campaign_id);
+    ctx.vault_ata = client::get_vault_address(
+        &ctx.fundraiser,
+        &ctx.mint,
+        &ctx.token_program,
+        ctx.vault_kind,
+    );
+    assert_ne!(ctx.fundraiser, first_fundraiser);
+
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, 2 * DONATION_AMOUNT);
+
+    for (fundraiser, campaign_id, raised) in [
+        (first_fundraiser, ctx.campaign_id - 1, DONATION_AMOUNT),
+        (ctx.fundraiser, ctx.campaign_id, 2 * DONATION_AMOUNT),
+    ] {
+        let pda = ctx.svm.get_account(&fundraiser).unwrap();
+        let fundraise_pda =
+            ::wincode::deserialize::<Fundraiser>(&pda.data).expect("unable to deserialize ");
+        assert_eq!(campaign_id, u64::from_le_bytes(fundraise_pda.campaign_id));
+        assert_eq!(raised, u64::from_le_bytes(fundraise_pda.current_amount));
+    }
+}
+
+fn forge_fundraiser(ctx: &mut crate::TestContext) -> Pubkey {
+    let forged = Pubkey::new_unique();
+    let fundraiser = ctx.svm.get_account(&ctx.fundraiser).unwrap();
+    ctx.svm.set_account(forged, fundraiser).unwrap();
+    forged
+}
+
+#[test]
+pub fn test_contribution_inx_fails_with_forged_fundraiser() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    let forged = forge_fundraiser(&mut ctx);
+    let mut ix = contribution_ix(&ctx, DONATION_AMOUNT);
+    ix.accounts[2].pubkey = forged;
+    let donar = ctx.donar.pubkey();
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&ctx.donar], &donar);
+    assert_fundraiser_error(result, FundraiserError::InvalidFundraiser);
+}
+
+#[test]
+pub fn test_contribution_inx_fails_if_fundraiser_not_program_owned() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    let mut fundraiser = ctx.svm.get_account(&ctx.fundraiser).unwrap();
+    fundraiser.owner = ctx.system_program;
+    ctx.svm.set_account(ctx.fundraiser, fundraiser).unwrap();
+    let result = try_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    assert_instruction_error(result, InstructionError::InvalidAccountOwner);
+}
+
+#[test]
+pub fn test_refund_inx_fails_with_forged_fundraiser() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    set_clock(&mut ctx.svm, END_TS);
+    let forged = forge_fundraiser(&mut ctx);
+    let mut ix = refund_ix(&ctx);
+    ix.accounts[3].pubkey = forged;
+    let donar = ctx.donar.pubkey();
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&ctx.donar], &donar);
+    assert_fundraiser_error(result, FundraiserError::InvalidFundraiser);
+}
+
+#[test]
+pub fn test_refund_inx_fails_if_maker_does_not_match() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    set_clock(&mut ctx.svm, END_TS);
+    let mut ix = refund_ix(&ctx);
+    ix.accounts[1].pubkey = ctx.donar.pubkey();
+    let donar = ctx.donar.pubkey();
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&ctx.donar], &donar);
+    assert_fundraiser_error(result, FundraiserError::MakerMismatch);
+}
+
+#[test]
+pub fn test_checkout_inx_fails_with_forged_fundraiser() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    set_clock(&mut ctx.svm, END_TS);
+    let forged = forge_fundraiser(&mut ctx);
+    let mut ix = checkout_ix(&ctx);
+    ix.accounts[2].pubkey = forged;
+    let maker = ctx.maker.pubkey();
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&ctx.maker], &maker);
+    assert_fundraiser_error(result, FundraiserError::InvalidFundraiser);
+}
+
+fn funded_checkout_context() -> crate::TestContext {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    set_clock(&mut ctx.svm, END_TS);
+    ctx
+}
+
+fn funded_keypair(ctx: &mut crate::TestContext) -> Keypair {
+    let keypair = Keypair::new();
+    ctx.svm
+        .airdrop(&keypair.pubkey(), 10 * LAMPORTS_PER_SOL)
+        .expect("Airdrop failed");
+    keypair
+}
+
+/// Another contributor with an associated token account holding the goal amount.
+fn funded_contributor(ctx: &mut crate::TestContext) -> Keypair {
+    let keypair = funded_keypair(ctx);
+    let ata = CreateAssociatedTokenAccount::new(&mut ctx.svm, &keypair, &ctx.mint)
+        .owner(&keypair.pubkey())
+        .token_program_id(&ctx.token_program)
+        .send()
+        .unwrap();
+    MintTo::new(&mut ctx.svm, &ctx.donar, &ctx.mint, &ata, AMOUNT_TO_RAISE)
+        .token_program_id(&ctx.token_program)
+        .send()
+        .unwrap();
+    keypair
+}
+
+#[test]
+pub fn test_checkout_inx_fails_if_signer_is_not_maker() {
+    let mut ctx = funded_checkout_context();
+    let attacker = funded_keypair(&mut ctx);
+    let attacker_ata = CreateAssociatedTokenAccount::new(&mut ctx.svm, &attacker, &ctx.mint)
+        .owner(&attacker.pubkey())
+        .token_program_id(&ctx.token_program)
+        .send()
+        .unwrap();
+    let mut ix = checkout_ix(&ctx);
+    ix.accounts[0].pubkey = attacker.pubkey();
+    ix.accounts[4].pubkey = attacker_ata;
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&attacker], &attacker.pubkey());
+    assert_fundraiser_error(result, FundraiserError::MakerMismatch);
+}
+
+#[test]
+pub fn test_checkout_inx_fails_if_maker_does_not_sign() {
+    let mut ctx = funded_checkout_context();
+    let attacker = funded_keypair(&mut ctx);
+    let mut ix = checkout_ix(&ctx);
+    ix.accounts[0].is_signer = false;
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&attacker], &attacker.pubkey());
+    assert_instruction_error(result, InstructionError::MissingRequiredSignature);
+}
+
+#[test]
+pub fn test_checkout_inx_fails_if_vault_is_not_owned_by_fundraiser() {
+    let mut ctx = funded_checkout_context();
+    let attacker = funded_keypair(&mut ctx);
+    let attacker_ata = CreateAssociatedTokenAccount::new(&mut ctx.svm, &attacker, &ctx.mint)
+        .owner(&attacker.pubkey())
+        .token_program_id(&ctx.token_program)
+        .send()
+        .unwrap();
+    let mut ix = checkout_ix(&ctx);
+    ix.accounts[3].pubkey = attacker_ata;
+    let maker = ctx.maker.pubkey();
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&ctx.maker], &maker);
+    assert_fundraiser_error(result, FundraiserError::InvalidVault);
+}
+
+#[test]
+pub fn test_checkout_inx_fails_if_vault_mint_is_wrong() {
+    let mut ctx = funded_checkout_context();
+    let attacker = funded_keypair(&mut ctx);
+    let other_mint = CreateMint::new(&mut ctx.svm, &attacker)
+        .decimals(6)
+        .token_program_id(&ctx.token_program)
+        .send()
+        .unwrap();
+    let other_vault = CreateAssociatedTokenAccount::new(&mut ctx.svm, &attacker, &other_mint)
+        .owner(&ctx.fundraiser)
+        .token_program_id(&ctx.token_program)
+        .send()
+        .unwrap();
+    let mut ix = checkout_ix(&ctx);
+    ix.accounts[3].pubkey = other_vault;
+    let maker = ctx.maker.pubkey();
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&ctx.maker], &maker);
+    assert_fundraiser_error(result, FundraiserError::InvalidVault);
+}
+
+#[test]
+pub fn test_checkout_inx_fails_if_vault_is_not_the_associated_token_account() {
+    let mut ctx = funded_checkout_context();
+    let attacker = funded_keypair(&mut ctx);
+    // owned by the fundraiser and of the right mint, but not at its associated address
+    let other_vault = CreateAccount::new(&mut ctx.svm, &attacker, &ctx.mint)
+        .owner(&ctx.fundraiser)
+        .token_program_id(&ctx.token_program)
+        .send()
+        .unwrap();
+    let mut ix = checkout_ix(&ctx);
+    ix.accounts[3].pubkey = other_vault;
+    let maker = ctx.maker.pubkey();
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&ctx.maker], &maker);
+    assert_fundraiser_error(result, FundraiserError::InvalidVault);
+}
+
+/// A campaign that ended below its goal, with the donar and one other contributor.
+fn failed_two_contributor_context() -> (crate::TestContext, Keypair) {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    let other = funded_contributor(&mut ctx);
+    let ix = client::contribute(
+        &other.pubkey(),
+        &ctx.maker.pubkey(),
+        ctx.campaign_id,
+        &ctx.mint,
+        &ctx.token_program,
+        ctx.vault_kind,
+        2 * DONATION_AMOUNT,
+    );
+    send_transaction(&mut ctx.svm, ix, &[&other], &other.pubkey());
+    set_clock(&mut ctx.svm, END_TS);
+    (ctx, other)
+}
+
+#[test]
+pub fn test_refund_batch_inx_refunds_each_contributor_and_closes_the_campaign() {
+    let (mut ctx, other) = failed_two_contributor_context();
+    let crank = funded_keypair(&mut ctx);
+    let other_ata =
+        client::get_associated_token_address(&other.pubkey(), &ctx.mint, &ctx.token_program);
+    let (other_contribution, _) =
+        client::find_contribution_address(&ctx.fundraiser, &other.pubkey());
+    let donar_tokens_before = get_spl_account::<Account>(&ctx.svm, &ctx.donar_ata)
+        .unwrap()
+        .amount;
+    let donar_lamports_before = ctx.svm.get_balance(&ctx.donar.pubkey()).unwrap();
+    let contribution_rent = ctx.svm.get_balance(&ctx.contribution).unwrap();
+
+    let ix = refund_batch_ix(&ctx, &crank.pubkey(), &[ctx.donar.pubkey()]);
+    send_transaction(&mut ctx.svm, ix, &[&crank], &crank.pubkey());
+    assert_eq!(
+        get_spl_account::<Account>(&ctx.svm, &ctx.donar_ata)
+            .unwrap()
+            .amount,
+        donar_tokens_before + DONATION_AMOUNT
+    );
+    assert_eq!(
+        ctx.svm.get_balance(&ctx.donar.pubkey()).unwrap(),
+        donar_lamports_before + contribution_rent
+    );
+    assert_eq!(ctx.svm.get_balance(&ctx.contribution).unwrap_or(0), 0);
+    // the other contribution is still in the vault, so the campaign stays open
+    assert!(ctx.svm.get_account(&ctx.vault_ata).is_some());
+
+    let ix = refund_batch_ix(&ctx, &crank.pubkey(), &[other.pubkey()]);
+    send_transaction(&mut ctx.svm, ix, &[&crank], &crank.pubkey());
+    assert_eq!(
+        get_spl_account::<Account>(&ctx.svm, &other_ata)
+            .unwrap()
+            .amount,
+        AMOUNT_TO_RAISE
+    );
+    assert_eq!(ctx.svm.get_balance(&other_contribution).unwrap_or(0), 0);
+    assert_eq!(ctx.svm.get_balance(&ctx.vault_ata).unwrap_or(0), 0);
+    assert_eq!(ctx.svm.get_balance(&ctx.fundraiser).unwrap_or(0), 0);
+}
+
+#[test]
+pub fn test_refund_batch_inx_refunds_many_contributors_at_once() {
+    let (mut ctx, other) = failed_two_contributor_context();
+    let crank = funded_keypair(&mut ctx);
+    let ix = refund_batch_ix(&ctx, &crank.pubkey(), &[ctx.donar.pubkey(), other.pubkey()]);
+    send_transaction(&mut ctx.svm, ix, &[&crank], &crank.pubkey());
+    assert_eq!(ctx.svm.get_balance(&ctx.contribution).unwrap_or(0), 0);
+    assert_eq!(ctx.svm.get_balance(&ctx.vault_ata).unwrap_or(0), 0);
+    assert_eq!(ctx.svm.get_balance(&ctx.fundraiser).unwrap_or(0), 0);
+}
+
+#[test]
+pub fn test_refund_batch_inx_fails_if_contributor_does_not_match() {
+    let (mut ctx, other) = failed_two_contributor_context();
+    let crank = funded_keypair(&mut ctx);
+    let mut ix = refund_batch_ix(&ctx, &crank.pubkey(), &[ctx.donar.pubkey()]);
+    // pay the donar's contribution to someone else
+    ix.accounts[9].pubkey = other.pubkey();
+    ix.accounts[10].pubkey =
+        client::get_associated_token_address(&other.pubkey(), &ctx.mint, &ctx.token_program);
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&crank], &crank.pubkey());
+    assert_fundraiser_error(result, FundraiserError::ContributorMismatch);
+}
+
+#[test]
+pub fn test_refund_batch_inx_fails_if_token_account_is_not_the_contributors() {
+    let (mut ctx, other) = failed_two_contributor_context();
+    let crank = funded_keypair(&mut ctx);
+    let mut ix = refund_batch_ix(&ctx, &crank.pubkey(), &[ctx.donar.pubkey()]);
+    ix.accounts[10].pubkey =
+        client::get_associated_token_address(&other.pubkey(), &ctx.mint, &ctx.token_program);
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&crank], &crank.pubkey());
+    assert_instruction_error(result, InstructionError::InvalidAccountData);
+}
+
+#[test]
+pub fn test_refund_batch_inx_fails_if_contribution_is_repeated() {
+    let (mut ctx, _) = failed_two_contributor_context();
+    let crank = funded_keypair(&mut ctx);
+    let ix = refund_batch_ix(
+        &ctx,
+        &crank.pubkey(),
+        &[ctx.donar.pubkey(), ctx.donar.pubkey()],
+    );
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&crank], &crank.pubkey());
+    assert_instruction_error(result, InstructionError::InvalidAccountOwner);
+}
+
+#[test]
+pub fn test_refund_batch_inx_fails_if_goal_reached() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    set_clock(&mut ctx.svm, END_TS);
+    let crank = funded_keypair(&mut ctx);
+    let ix = refund_batch_ix(&ctx, &crank.pubkey(), &[ctx.donar.pubkey()]);
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&crank], &crank.pubkey());
+    assert_fundraiser_error(result, FundraiserError::GoalReached);
+}
+
+#[test]
+pub fn test_refund_batch_inx_fails_without_complete_triples() {
+    let (mut ctx, _) = failed_two_contributor_context();
+    let crank = funded_keypair(&mut ctx);
+    let mut ix = refund_batch_ix(&ctx, &crank.pubkey(), &[ctx.donar.pubkey()]);
+    ix.accounts.pop();
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&crank], &crank.pubkey());
+    assert_instruction_error(result, InstructionError::MissingAccount);
+}
+
+#[test]
+pub fn test_refund_batch_inx_fails_with_a_look_alike_vault() {
+    let (mut ctx, _) = failed_two_contributor_context();
+    let crank = funded_keypair(&mut ctx);
+    // a token account of the fundraiser holding just the donar's contribution, which would
+    // look settled once the donar is refunded out of it
+    let look_alike = CreateAccount::new(&mut ctx.svm, &crank, &ctx.mint)
+        .owner(&ctx.fundraiser)
+        .token_program_id(&ctx.token_program)
+        .send()
+        .unwrap();
+    MintTo::new(
+        &mut ctx.svm,
+        &ctx.donar,
+        &ctx.mint,
+        &look_alike,
+        DONATION_AMOUNT,
+    )
+    .token_program_id(&ctx.token_program)
+    .send()
+    .unwrap();
+    let mut ix = refund_batch_ix(&ctx, &crank.pubkey(), &[ctx.donar.pubkey()]);
+    ix.accounts[3].pubkey = look_alike;
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&crank], &crank.pubkey());
+    assert_fundraiser_error(result, FundraiserError::InvalidVault);
+}
+
+#[test]
+pub fn test_refund_closes_the_campaign_despite_tokens_sent_to_the_vault() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    let stray = 1_000;
+    MintTo::new(&mut ctx.svm, &ctx.donar, &ctx.mint, &ctx.vault_ata, stray)
+        .token_program_id(&ctx.token_program)
+        .send()
+        .unwrap();
+    set_clock(&mut ctx.svm, END_TS);
+    send_refund_transaction(&mut ctx);
+
+    // the last refund takes what was sent to the vault along with it
+    let donar_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.donar_ata).expect("token account not found");
+    assert_eq!(donar_ata.amount, 2 * AMOUNT_TO_RAISE + stray);
+    assert_eq!(ctx.svm.get_balance(&ctx.vault_ata).unwrap_or(0), 0);
+    assert_eq!(ctx.svm.get_balance(&ctx.fundraiser).unwrap_or(0), 0);
+}
+
+#[test]
+pub fn test_close_contribution_inx_after_checkout() {
+    let mut ctx = funded_checkout_context();
+    send_checkout_transaction(&mut ctx);
+    let contribution_rent = ctx.svm.get_balance(&ctx.contribution).unwrap();
+    let donar_before = ctx.svm.get_balance(&ctx.donar.pubkey()).unwrap();
+    send_close_contribution_transaction(&mut ctx);
+    assert_eq!(ctx.svm.get_balance(&ctx.contribution).unwrap_or(0), 0);
+    let fee = 5_000;
+    assert_eq!(
+        ctx.svm.get_balance(&ctx.donar.pubkey()).unwrap(),
+        donar_before + contribution_rent - fee
+    );
+}
+
+#[test]
+pub fn test_close_contribution_inx_once_goal_reached_before_checkout() {
+    let mut ctx = funded_checkout_context();
+    send_close_contribution_transaction(&mut ctx);
+    assert_eq!(ctx.svm.get_balance(&ctx.contribution).unwrap_or(0), 0);
+    // the maker's checkout is unaffected
+    send_checkout_transaction(&mut ctx);
+    let maker_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.maker_ata).expect("token account not found");
+    assert_eq!(maker_ata.amount, AMOUNT_TO_RAISE);
+}
+
+#[test]
+pub fn test_close_contribution_inx_fails_while_active() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    let result = try_close_contribution_transaction(&mut ctx);
+    assert_fundraiser_error(result, FundraiserError::FundraiserNotEnded);
+}
+
+#[test]
+pub fn test_close_contribution_inx_fails_if_refund_is_owed() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    set_clock(&mut ctx.svm, END_TS);
+    let result = try_close_contribution_transaction(&mut ctx);
+    assert_fundraiser_error(result, FundraiserError::GoalNotReached);
+}
+
+#[test]
+pub fn test_close_contribution_inx_fails_if_signer_is_not_contributor() {
+    let mut ctx = funded_checkout_context();
+    send_checkout_transaction(&mut ctx);
+    let attacker = funded_keypair(&mut ctx);
+    let mut ix = close_contribution_ix(&ctx);
+    ix.accounts[0].pubkey = attacker.pubkey();
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&attacker], &attacker.pubkey());
+    assert_fundraiser_error(result, FundraiserError::ContributorMismatch);
+}
+
+#[test]
+pub fn test_recreated_campaign_rejects_stale_contributions() {
+    let mut ctx = setup();
+    ctx.early_checkout = true;
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    send_checkout_transaction(&mut ctx);
+
+    // the maker re-creates the campaign at the same address, within the same second
+    ctx.svm.expire_blockhash();
+    try_initialize_transaction(&mut ctx, AMOUNT_TO_RAISE, None, END_TS).unwrap();
+    let result = try_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    assert_fundraiser_error(result, FundraiserError::StaleContribution);
+
+    // the leftover contribution can still be closed, after which contributing works again
+    send_close_contribution_transaction(&mut ctx);
+    ctx.svm.expire_blockhash();
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    let pda = ctx.svm.get_account(&ctx.contribution).unwrap();
+    let contribution =
+        ::wincode::deserialize::<Contribution>(&pda.data).expect("unable to deserialize");
+    assert_eq!(u64::from_le_bytes(contribution.amount), DONATION_AMOUNT);
+    assert_eq!(u64::from_le_bytes(contribution.campaign_nonce), 2);
+}
+
+#[test]
+pub fn test_init_inx_numbers_the_makers_campaigns() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    ctx.campaign_id += 1;
+    (ctx.fundraiser, _) = client::find_fundraiser_address(&ctx.maker.pubkey(), ctx.campaign_id);
+    ctx.vault_ata = client::get_vault_address(
+        &ctx.fundraiser,
+        &ctx.mint,
+        &ctx.token_program,
+        ctx.vault_kind,
+    );
+    send_initialize_transaction(&mut ctx);
+
+    let pda = ctx.svm.get_account(&ctx.fundraiser).unwrap();
+    let fundraiser =
+        ::wincode::deserialize::<Fundraiser>(&pda.data).expect("unable to deserialize");
+    assert_eq!(u64::from_le_bytes(fundraiser.campaign_nonce), 2);
+    let (maker_account, _) = client::find_maker_address(&ctx.maker.pubkey());
+    let pda = ctx.svm.get_account(&maker_account).unwrap();
+    let maker = ::wincode::deserialize::<Maker>(&pda.data).expect("unable to deserialize");
+    assert_eq!(u64::from_le_bytes(maker.campaign_count), 2);
+}
+
+#[test]
+pub fn test_init_inx_fails_for_a_non_canonical_maker_bump() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    let (_, canonical_bump) = client::find_maker_address(&ctx.maker.pubkey());
+    // a second maker account would count the maker's campaigns from one again
+    let (maker_account, maker_bump) = (0..canonical_bump)
+        .rev()
+        .find_map(|bump| {
+            Pubkey::create_program_address(
+                &[MAKER_SEED, ctx.maker.pubkey().as_ref(), &[bump]],
+                &pinocchio_fundraiser::ID,
+            )
+            .ok()
+            .map(|address| (address, bump))
+        })
+        .unwrap();
+    let mut init_ix = initialize_ix(&ctx, AMOUNT_TO_RAISE, None, END_TS);
+    let Ok(FundraiserInstruction::Initialize(mut data)) =
+        FundraiserInstruction::unpack(&init_ix.data)
+    else {
+        panic!("not an initialize instruction");
+    };
+    data.maker_bump = maker_bump;
+    init_ix.data = FundraiserInstruction::Initialize(data).pack();
+    init_ix.accounts[6].pubkey = maker_account;
+    let maker_pubkey = ctx.maker.pubkey();
+    let result = try_send_transaction(&mut ctx.svm, init_ix, &[&ctx.maker], &maker_pubkey);
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+}
+
+#[test]
+pub fn test_init_inx_creates_a_pre_funded_maker_account() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    let (maker_account, _) = client::find_maker_address(&ctx.maker.pubkey());
+    ctx.svm.airdrop(&maker_account, 1_000).unwrap();
+
+    send_initialize_transaction(&mut ctx);
+    let pda = ctx.svm.get_account(&maker_account).unwrap();
+    assert_eq!(pda.owner, pinocchio_fundraiser::ID);
+    assert_eq!(
+        pda.lamports,
+        ctx.svm.minimum_balance_for_rent_exemption(Maker::LEN)
+    );
+    let maker = ::wincode::deserialize::<Maker>(&pda.data).expect("unable to deserialize");
+    assert_eq!(u64::from_le_bytes(maker.campaign_count), 1);
+}
+
+#[test]
+pub fn test_token_2022_campaign_checkout() {
+    let mut ctx = setup_with_token_program(TOKEN_2022_PROGRAM_ID);
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    set_clock(&mut ctx.svm, END_TS);
+    send_checkout_transaction(&mut ctx);
+    let maker_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.maker_ata).expect("token account not found");
+    assert_eq!(maker_ata.amount, AMOUNT_TO_RAISE);
+    assert_eq!(ctx.svm.get_balance(&ctx.vault_ata).unwrap_or(0), 0);
+}
+
+#[test]
+pub fn test_token_2022_campaign_refund() {
+    let mut ctx = setup_with_token_program(TOKEN_2022_PROGRAM_ID);
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    set_clock(&mut ctx.svm, END_TS);
+    send_refund_transaction(&mut ctx);
+    let donar_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.donar_ata).expect("token account not found");
+    assert_eq!(donar_ata.amount, 2 * AMOUNT_TO_RAISE);
+    assert_eq!(ctx.svm.get_balance(&ctx.fundraiser).unwrap_or(0), 0);
+}
+
+/// 1% of every transfer of the transfer fee mint is withheld.
+const TRANSFER_FEE_BPS: u16 = 100;
+
+#[test]
+pub fn test_transfer_fee_contribution_credits_amount_received() {
+    let mut ctx = setup_with_transfer_fee(TRANSFER_FEE_BPS);
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+
+    let received = DONATION_AMOUNT - DONATION_AMOUNT / 100;
+    let pda = ctx.svm.get_account(&ctx.contribution).unwrap();
+    let contribution =
+        ::wincode::deserialize::<Contribution>(&pda.data).expect("unable to deserialize");
+    assert_eq!(u64::from_le_bytes(contribution.amount), received);
+    let pda = ctx.svm.get_account(&ctx.fundraiser).unwrap();
+    let fundraiser =
+        ::wincode::deserialize::<Fundraiser>(&pda.data).expect("unable to deserialize");
+    assert_eq!(u64::from_le_bytes(fundraiser.current_amount), received);
+    let vault_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.vault_ata).expect("token account not found");
+    assert_eq!(vault_ata.amount, received);
+}
+
+#[test]
+pub fn test_transfer_fee_contributions_count_towards_goal_after_fees() {
+    let mut ctx = setup_with_transfer_fee(TRANSFER_FEE_BPS);
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    set_clock(&mut ctx.svm, END_TS);
+    let result = try_checkout_transaction(&mut ctx);
+    assert_fundraiser_error(result, FundraiserError::GoalNotReached);
+}
+
+#[test]
+pub fn test_transfer_fee_refund_pays_back_what_the_vault_holds() {
+    let mut ctx = setup_with_transfer_fee(TRANSFER_FEE_BPS);
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    set_clock(&mut ctx.svm, END_TS);
+    send_refund_transaction(&mut ctx);
+
+    // both transfers pay the fee: 500_000 -> 495_000 in the vault -> 490_050 back
+    let donar_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.donar_ata).expect("token account not found");
+    assert_eq!(
+        donar_ata.amount,
+        2 * AMOUNT_TO_RAISE - DONATION_AMOUNT + 490_050
+    );
+    assert_eq!(ctx.svm.get_balance(&ctx.vault_ata).unwrap_or(0), 0);
+    assert_eq!(ctx.svm.get_balance(&ctx.fundraiser).unwrap_or(0), 0);
+}
+
+#[test]
+pub fn test_transfer_fee_checkout_sweeps_the_vault() {
+    let mut ctx = setup_with_transfer_fee(TRANSFER_FEE_BPS);
+    ctx.funding_mode = FundingMode::KeepWhatYouRaise;
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    set_clock(&mut ctx.svm, END_TS);
+    send_checkout_transaction(&mut ctx);
+
+    // 99_000_000 reached the vault, and the maker receives it less the outgoing fee
+    let maker_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.maker_ata).expect("token account not found");
+    assert_eq!(maker_ata.amount, 98_010_000);
+    assert_eq!(ctx.svm.get_balance(&ctx.vault_ata).unwrap_or(0), 0);
+}
+
+#[test]
+pub fn test_pda_vault_is_a_token_account_owned_by_the_fundraiser() {
+    let mut ctx = with_pda_vault(setup());
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    assert_eq!(
+        ctx.vault_ata,
+        client::get_vault_address(
+            &ctx.fundraiser,
+            &ctx.mint,
+            &ctx.token_program,
+            VaultKind::Pda
+        )
+    );
+    assert_eq!(
+        ctx.svm.get_account(&ctx.vault_ata).unwrap().owner,
+        ctx.token_program
+    );
+    let vault: Account =
+        get_spl_account(&ctx.svm, &ctx.vault_ata).expect("token account not found");
+    assert_eq!(vault.owner, ctx.fundraiser);
+    assert_eq!(vault.mint, ctx.mint);
+
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    let vault: Account =
+        get_spl_account(&ctx.svm, &ctx.vault_ata).expect("token account not found");
+    assert_eq!(vault.amount, DONATION_AMOUNT);
+}
+
+#[test]
+pub fn test_pda_vault_checkout() {
+    let mut ctx = with_pda_vault(setup());
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    set_clock(&mut ctx.svm, END_TS);
+    send_checkout_transaction(&mut ctx);
+    let maker_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.maker_ata).expect("token account not found");
+    assert_eq!(maker_ata.amount, AMOUNT_TO_RAISE);
+    assert_eq!(ctx.svm.get_balance(&ctx.vault_ata).unwrap_or(0), 0);
+}
+
+#[test]
+pub fn test_pda_vault_refund() {
+    let mut ctx = with_pda_vault(setup());
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    set_clock(&mut ctx.svm, END_TS);
+    send_refund_transaction(&mut ctx);
+    let donar_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.donar_ata).expect("token account not found");
+    assert_eq!(donar_ata.amount, 2 * AMOUNT_TO_RAISE);
+    assert_eq!(ctx.svm.get_balance(&ctx.vault_ata).unwrap_or(0), 0);
+    assert_eq!(ctx.svm.get_balance(&ctx.fundraiser).unwrap_or(0), 0);
+}
+
+#[test]
+pub fn test_pda_vault_with_transfer_fee_mint() {
+    // the vault needs room for the transfer fee amount extension
+    let mut ctx = with_pda_vault(setup_with_transfer_fee(TRANSFER_FEE_BPS));
+    ctx.funding_mode = FundingMode::KeepWhatYouRaise;
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    set_clock(&mut ctx.svm, END_TS);
+    send_checkout_transaction(&mut ctx);
+    let maker_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.maker_ata).expect("token account not found");
+    assert_eq!(maker_ata.amount, 98_010_000);
+    assert_eq!(ctx.svm.get_balance(&ctx.vault_ata).unwrap_or(0), 0);
+}
+
+#[test]
+pub fn test_pda_vault_checkout_fails_with_another_fundraiser_token_account() {
+    let mut ctx = with_pda_vault(setup());
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    set_clock(&mut ctx.svm, END_TS);
+    // owned by the fundraiser and of the right mint, but not at the vault PDA
+    let fundraiser_ata = CreateAssociatedTokenAccount::new(&mut ctx.svm, &ctx.maker, &ctx.mint)
+        .owner(&ctx.fundraiser)
+        .token_program_id(&ctx.token_program)
+        .send()
+        .unwrap();
+    let mut ix = checkout_ix(&ctx);
+    ix.accounts[3].pubkey = fundraiser_ata;
+    let maker = ctx.maker.pubkey();
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&ctx.maker], &maker);
+    assert_fundraiser_error(result, FundraiserError::InvalidVault);
+}
+
+/// Compute units consumed by initialize, contribute and checkout of a campaign that
+/// reaches its goal.
+fn campaign_compute_units(mut ctx: TestContext) -> [u64; 3] {
+    set_clock(&mut ctx.svm, START_TS);
+    let maker = ctx.maker.pubkey();
+    let donar = ctx.donar.pubkey();
+    let ix = initialize_ix(&ctx, AMOUNT_TO_RAISE, None, END_TS);
+    let initialize = try_send_transaction(&mut ctx.svm, ix, &[&ctx.maker], &maker)
+        .expect("Transaction should succeed")
+        .compute_units_consumed;
+    let ix = contribution_ix(&ctx, AMOUNT_TO_RAISE);
+    let contribute = try_send_transaction(&mut ctx.svm, ix, &[&ctx.donar], &donar)
+        .expect("Transaction should succeed")
+        .compute_units_consumed;
+    set_clock(&mut ctx.svm, END_TS);
+    let ix = checkout_ix(&ctx);
+    let checkout = try_send_transaction(&mut ctx.svm, ix, &[&ctx.maker], &maker)
+        .expect("Transaction should succeed")
+        .compute_units_consumed;
+    [initialize, contribute, checkout]
+}
+
+#[test]
+pub fn test_vault_kinds_compute_units() {
+    let associated_token = campaign_compute_units(setup());
+    let pda = campaign_compute_units(with_pda_vault(setup()));
+    println!("{:<12}{:>18}{:>10}", "CUs", "associated token", "PDA");
+    for (index, name) in ["initialize", "contribute", "checkout"].iter().enumerate() {
+        println!(
+            "{:<12}{:>18}{:>10}",
+            name, associated_token[index], pda[index]
+        );
+    }
+    // the PDA vault skips the associated token program CPI
+    assert!(pda[0] < associated_token[0]);
+}
+
+#[test]
+pub fn test_native_sol_contribution_moves_lamports_to_vault() {
+    let mut ctx = setup_native_sol();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    let vault_rent = ctx.svm.get_balance(&ctx.vault_ata).unwrap();
+    assert_eq!(vault_rent, ctx.svm.minimum_balance_for_rent_exemption(0));
+
+    let donar_before = ctx.svm.get_balance(&ctx.donar.pubkey()).unwrap();
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    assert_eq!(
+        ctx.svm.get_balance(&ctx.vault_ata).unwrap(),
+        vault_rent + DONATION_AMOUNT
+    );
+    let pda = ctx.svm.get_account(&ctx.fundraiser).unwrap();
+    let fundraiser =
+        ::wincode::deserialize::<Fundraiser>(&pda.data).expect("unable to deserialize");
+    assert_eq!(
+        u64::from_le_bytes(fundraiser.current_amount),
+        DONATION_AMOUNT
+    );
+    // the donar also pays the contribution account's rent and the transaction fee
+    let contribution_rent = ctx.svm.get_balance(&ctx.contribution).unwrap();
+    assert_eq!(
+        ctx.svm.get_balance(&ctx.donar.pubkey()).unwrap(),
+        donar_before - DONATION_AMOUNT - contribution_rent - 5_000
+    );
+}
+
+#[test]
+pub fn test_native_sol_checkout_pays_maker_in_lamports() {
+    let mut ctx = setup_native_sol();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    set_clock(&mut ctx.svm, END_TS);
+
+    let maker_before = ctx.svm.get_balance(&ctx.maker.pubkey()).unwrap();
+    let released = ctx.svm.get_balance(&ctx.vault_ata).unwrap()
+        + ctx.svm.get_balance(&ctx.fundraiser).unwrap();
+    send_checkout_transaction(&mut ctx);
+    // the raised lamports plus the vault and fundraiser rent, less the transaction fee
+    let maker_after = ctx.svm.get_balance(&ctx.maker.pubkey()).unwrap();
+    assert_eq!(maker_after, maker_before + released - 5_000);
+    assert_eq!(ctx.svm.get_balance(&ctx.vault_ata).unwrap_or(0), 0);
+    assert_eq!(ctx.svm.get_balance(&ctx.fundraiser).unwrap_or(0), 0);
+}
+
+#[test]
+pub fn test_native_sol_refund_returns_lamports() {
+    let mut ctx = setup_native_sol();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    set_clock(&mut ctx.svm, END_TS);
+
+    let donar_before = ctx.svm.get_balance(&ctx.donar.pubkey()).unwrap();
+    let contribution_rent = ctx.svm.get_balance(&ctx.contribution).unwrap();
+    send_refund_transaction(&mut ctx);
+    assert_eq!(
+        ctx.svm.get_balance(&ctx.donar.pubkey()).unwrap(),
+        donar_before + DONATION_AMOUNT + contribution_rent - 5_000
+    );
+    assert_eq!(ctx.svm.get_balance(&ctx.vault_ata).unwrap_or(0), 0);
+    assert_eq!(ctx.svm.get_balance(&ctx.fundraiser).unwrap_or(0), 0);
+}
+
+#[test]
+pub fn test_native_sol_withdraw_keeps_vault_rent_exempt() {
+    let mut ctx = setup_native_sol();
+    ctx.withdrawal_policy = WithdrawalPolicy::Anytime;
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    send_withdraw_transaction(&mut ctx, DONATION_AMOUNT);
+    assert_eq!(
+        ctx.svm.get_balance(&ctx.vault_ata).unwrap(),
+        ctx.svm.minimum_balance_for_rent_exemption(0)
+    );
+}
+
+#[test]
+pub fn test_native_sol_checkout_fails_if_vault_is_not_the_pda() {
+    let mut ctx = setup_native_sol();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, AMOUNT_TO_RAISE);
+    set_clock(&mut ctx.svm, END_TS);
+    let mut ix = checkout_ix(&ctx);
+    ix.accounts[3].pubkey = ctx.maker.pubkey();
+    let maker = ctx.maker.pubkey();
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&ctx.maker], &maker);
+    assert_fundraiser_error(result, FundraiserError::InvalidVault);
+}
+
+#[test]
+pub fn test_wrapped_sol_contribution_wraps_lamports_in_a_temporary_account() {
+    let mut ctx = setup_wrapped_sol();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+
+    let donar_before = ctx.svm.get_balance(&ctx.donar.pubkey()).unwrap();
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    let vault_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.vault_ata).expect("token account not found");
+    assert_eq!(vault_ata.amount, DONATION_AMOUNT);
+    // the wrapped SOL account was closed again, returning its rent
+    assert_eq!(ctx.svm.get_balance(&ctx.donar_ata).unwrap_or(0), 0);
+    let contribution_rent = ctx.svm.get_balance(&ctx.contribution).unwrap();
+    assert_eq!(
+        ctx.svm.get_balance(&ctx.donar.pubkey()).unwrap(),
+        donar_before - DONATION_AMOUNT - contribution_rent - 5_000
+    );
+}
+
+#[test]
+pub fn test_wrapped_sol_contribution_keeps_an_existing_wrapped_account() {
+    let mut ctx = setup_wrapped_sol();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    CreateAssociatedTokenAccount::new(&mut ctx.svm, &ctx.donar, &ctx.mint)
+        .owner(&ctx.donar.pubkey())
+        .token_program_id(&ctx.token_program)
+        .send()
+        .unwrap();
+
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    let donar_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.donar_ata).expect("token account not found");
+    assert_eq!(donar_ata.amount, 0);
+    let vault_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.vault_ata).expect("token account not found");
+    assert_eq!(vault_ata.amount, DONATION_AMOUNT);
+}
+
+#[test]
+pub fn test_wrapped_sol_refund_unwraps_to_lamports() {
+    let mut ctx = setup_wrapped_sol();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    set_clock(&mut ctx.svm, END_TS);
+
+    let donar_before = ctx.svm.get_balance(&ctx.donar.pubkey()).unwrap();
+    let contribution_rent = ctx.svm.get_balance(&ctx.contribution).unwrap();
+    let donar = ctx.donar.pubkey();
+    let ix = client::refund(
+        &donar,
+        &ctx.maker.pubkey(),
+        ctx.campaign_id,
+        &ctx.mint,
+        &ctx.token_program,
+        ctx.vault_kind,
+        true,
+    );
+    send_transaction(&mut ctx.svm, ix, &[&ctx.donar], &donar);
+    assert_eq!(
+        ctx.svm.get_balance(&donar).unwrap(),
+        donar_before + DONATION_AMOUNT + contribution_rent - 5_000
+    );
+    assert_eq!(ctx.svm.get_balance(&ctx.donar_ata).unwrap_or(0), 0);
+    assert_eq!(ctx.svm.get_balance(&ctx.vault_ata).unwrap_or(0), 0);
+}
+
+#[test]
+pub fn test_wrapped_sol_refund_pays_wrapped_sol_by_default() {
+    let mut ctx = setup_wrapped_sol();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    set_clock(&mut ctx.svm, END_TS);
+
+    // contribute closed the donar's wrapped SOL account, so refund recreates it
+    send_refund_transaction(&mut ctx);
+    let donar_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.donar_ata).expect("token account not found");
+    assert_eq!(donar_ata.amount, DONATION_AMOUNT);
+}
+
+#[test]
+pub fn test_wrapped_sol_refund_batch_recreates_the_wrapped_account() {
+    let mut ctx = setup_wrapped_sol();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    set_clock(&mut ctx.svm, END_TS);
+
+    let crank = funded_keypair(&mut ctx);
+    let ix = refund_batch_ix(&ctx, &crank.pubkey(), &[ctx.donar.pubkey()]);
+    send_transaction(&mut ctx.svm, ix, &[&crank], &crank.pubkey());
+    let donar_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.donar_ata).expect("token account not found");
+    assert_eq!(donar_ata.amount, DONATION_AMOUNT);
+    assert_eq!(ctx.svm.get_balance(&ctx.contribution).unwrap_or(0), 0);
+    assert_eq!(ctx.svm.get_balance(&ctx.fundraiser).unwrap_or(0), 0);
+}
+
+#[test]
+pub fn test_wrapped_sol_withdraw_recreates_the_wrapped_account() {
+    let mut ctx = setup_wrapped_sol();
+    ctx.withdrawal_policy = WithdrawalPolicy::Anytime;
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+
+    send_withdraw_transaction(&mut ctx, DONATION_AMOUNT);
+    let donar_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.donar_ata).expect("token account not found");
+    assert_eq!(donar_ata.amount, DONATION_AMOUNT);
+    let vault_ata: Account =
+        get_spl_account(&ctx.svm, &ctx.vault_ata).expect("token account not found");
+    assert_eq!(vault_ata.amount, 0);
+}
+
+#[test]
+pub fn test_refund_inx_fails_to_unwrap_other_mints() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    send_contribution_transaction(&mut ctx, DONATION_AMOUNT);
+    set_clock(&mut ctx.svm, END_TS);
+    let donar = ctx.donar.pubkey();
+    let ix = client::refund(
+        &donar,
+        &ctx.maker.pubkey(),
+        ctx.campaign_id,
+        &ctx.mint,
+        &ctx.token_program,
+        ctx.vault_kind,
+        true,
+    );
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&ctx.donar], &donar);
+    assert_instruction_error(result, InstructionError::InvalidArgument);
+}
+
+#[test]
+pub fn test_contribution_inx_fails_if_token_program_does_not_own_mint() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    send_initialize_transaction(&mut ctx);
+    let mut ix = contribution_ix(&ctx, DONATION_AMOUNT);
+    ix.accounts[6].pubkey = TOKEN_2022_PROGRAM_ID;
+    let donar = ctx.donar.pubkey();
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&ctx.donar], &donar);
+    assert_instruction_error(result, InstructionError::IncorrectProgramId);
+}
+
+#[test]
+pub fn test_init_inx_fails_if_token_program_is_not_supported() {
+    let mut ctx = setup();
+    set_clock(&mut ctx.svm, START_TS);
+    let mut ix = initialize_ix(&ctx, AMOUNT_TO_RAISE, None, END_TS);
+    ix.accounts[5].pubkey = ctx.system_program;
+    let maker = ctx.maker.pubkey();
+    let result = try_send_transaction(&mut ctx.svm, ix, &[&ctx.maker], &maker);
+    assert_instruction_error(result, InstructionError::IncorrectProgramId);
+}
+
+#[test]
+pub fn test_init_inx_fails_for_transfer_hook_mints() {
+    let mut ctx = setup_with_transfer_hook();
+    set_clock(&mut ctx.svm, START_TS);
+    let result = try_initialize_transaction(&mut ctx, AMOUNT_TO_RAISE, None, END_TS);
+    assert_fundraiser_error(result, FundraiserError::UnsupportedMint);
 }
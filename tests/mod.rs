@@ -1,5 +1,8 @@
+#![allow(clippy::result_large_err)]
+
 pub mod common;
 pub mod integration;
+pub mod unit;
 
 pub use common::*;
-pub use integration::*;
\ No newline at end of file
+pub use integration::*;
@@ -0,0 +1,416 @@
+use pinocchio_fundraiser::{
+    client,
+    instructions::{
+        ContributeData, FundraiserInstruction, InitializeData, RefundData, WithdrawData,
+    },
+    state::{FundingMode, VaultKind, WithdrawalPolicy},
+    token::TOKEN_2022_PROGRAM_ID,
+    vault::NATIVE_SOL,
+};
+use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+};
+
+use crate::fixtures::{
+    AMOUNT_TO_RAISE, CAMPAIGN_ID, CONTRIBUTION_LIMITS, DONATION_AMOUNT, END_TS, HARD_CAP,
+    TOKEN_PROGRAM_ID, program_id,
+};
+
+struct Keys {
+    maker: Pubkey,
+    contributor: Pubkey,
+    mint: Pubkey,
+    fundraiser: Pubkey,
+    fundraiser_bump: u8,
+    contribution: Pubkey,
+    contribution_bump: u8,
+}
+
+fn keys() -> Keys {
+    let maker = Pubkey::new_unique();
+    let contributor = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let (fundraiser, fundraiser_bump) = Pubkey::find_program_address(
+        &[b"fundraiser", maker.as_ref(), &CAMPAIGN_ID.to_le_bytes()],
+        &program_id(),
+    );
+    let (contribution, contribution_bump) = Pubkey::find_program_address(
+        &[b"contributor", fundraiser.as_ref(), contributor.as_ref()],
+        &program_id(),
+    );
+    Keys {
+        maker,
+        contributor,
+        mint,
+        fundraiser,
+        fundraiser_bump,
+        contribution,
+        contribution_bump,
+    }
+}
+
+#[test]
+pub fn test_client_derives_addresses() {
+    let k = keys();
+    assert_eq!(
+        client::find_fundraiser_address(&k.maker, CAMPAIGN_ID),
+        (k.fundraiser, k.fundraiser_bump)
+    );
+    assert_ne!(
+        client::find_fundraiser_address(&k.maker, CAMPAIGN_ID + 1).0,
+        k.fundraiser
+    );
+    assert_eq!(
+        client::find_contribution_address(&k.fundraiser, &k.contributor),
+        (k.contribution, k.contribution_bump)
+    );
+    assert_eq!(
+        client::get_vault_address(
+            &k.fundraiser,
+            &k.mint,
+            &TOKEN_PROGRAM_ID,
+            VaultKind::AssociatedToken
+        ),
+        get_associated_token_address(&k.fundraiser, &k.mint)
+    );
+    assert_eq!(
+        client::get_vault_address(
+            &k.fundraiser,
+            &k.mint,
+            &TOKEN_2022_PROGRAM_ID,
+            VaultKind::AssociatedToken
+        ),
+        get_associated_token_address_with_program_id(
+            &k.fundraiser,
+            &k.mint,
+            &TOKEN_2022_PROGRAM_ID
+        )
+    );
+}
+
+#[test]
+pub fn test_client_native_sol_uses_lamport_vault_and_wallets() {
+    let k = keys();
+    let (vault, _) =
+        Pubkey::find_program_address(&[b"vault", k.fundraiser.as_ref()], &program_id());
+    assert_eq!(client::find_vault_address(&k.fundraiser).0, vault);
+    assert_eq!(
+        client::get_vault_address(&k.fundraiser, &NATIVE_SOL, &NATIVE_SOL, VaultKind::Pda),
+        vault
+    );
+
+    let ix = client::contribute(
+        &k.contributor,
+        &k.maker,
+        CAMPAIGN_ID,
+        &NATIVE_SOL,
+        &NATIVE_SOL,
+        VaultKind::Pda,
+        DONATION_AMOUNT,
+    );
+    let accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(
+        accounts[..6],
+        [
+            k.contributor,
+            NATIVE_SOL,
+            k.fundraiser,
+            k.contributor,
+            k.contribution,
+            vault,
+        ]
+    );
+
+    let ix = client::checkout(
+        &k.maker,
+        CAMPAIGN_ID,
+        &NATIVE_SOL,
+        &NATIVE_SOL,
+        VaultKind::Pda,
+    );
+    let accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(
+        accounts[..5],
+        [k.maker, NATIVE_SOL, k.fundraiser, vault, k.maker]
+    );
+}
+
+#[test]
+pub fn test_client_initialize() {
+    let k = keys();
+    let ix = client::initialize(
+        &k.maker,
+        &k.mint,
+        &TOKEN_PROGRAM_ID,
+        &client::CampaignConfig {
+            campaign_id: CAMPAIGN_ID,
+            amount_to_raise: AMOUNT_TO_RAISE,
+            hard_cap: HARD_CAP,
+            start_ts: None,
+            end_ts: END_TS,
+            limits: CONTRIBUTION_LIMITS,
+            funding_mode: FundingMode::AllOrNothing,
+            early_checkout: false,
+            withdrawal_policy: WithdrawalPolicy::Disabled,
+            vault_kind: VaultKind::AssociatedToken,
+        },
+    );
+    let accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(ix.program_id, program_id());
+    assert_eq!(
+        accounts[..4],
+        [
+            k.maker,
+            k.mint,
+            k.fundraiser,
+            get_associated_token_address(&k.fundraiser, &k.mint),
+        ]
+    );
+    assert_eq!(
+        accounts[6],
+        Pubkey::find_program_address(&[b"maker", k.maker.as_ref()], &program_id()).0
+    );
+    assert!(ix.accounts[0].is_signer);
+    assert_eq!(
+        FundraiserInstruction::unpack(&ix.data).unwrap(),
+        FundraiserInstruction::Initialize(InitializeData {
+            bump: k.fundraiser_bump,
+            campaign_id: CAMPAIGN_ID,
+            amount_to_raise: AMOUNT_TO_RAISE,
+            hard_cap: HARD_CAP,
+            start_ts: None,
+            end_ts: END_TS,
+            limits: CONTRIBUTION_LIMITS,
+            funding_mode: FundingMode::AllOrNothing,
+            early_checkout: false,
+            withdrawal_policy: WithdrawalPolicy::Disabled,
+            vault_kind: VaultKind::AssociatedToken,
+            vault_bump: client::find_associated_token_address(
+                &k.fundraiser,
+                &k.mint,
+                &TOKEN_PROGRAM_ID
+            )
+            .1,
+            maker_bump: client::find_maker_address(&k.maker).1,
+        })
+    );
+}
+
+#[test]
+pub fn test_client_contribute() {
+    let k = keys();
+    let ix = client::contribute(
+        &k.contributor,
+        &k.maker,
+        CAMPAIGN_ID,
+        &k.mint,
+        &TOKEN_PROGRAM_ID,
+        VaultKind::AssociatedToken,
+        DONATION_AMOUNT,
+    );
+    let accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(
+        accounts[..6],
+        [
+            k.contributor,
+            k.mint,
+            k.fundraiser,
+            get_associated_token_address(&k.contributor, &k.mint),
+            k.contribution,
+            get_associated_token_address(&k.fundraiser, &k.mint),
+        ]
+    );
+    assert!(ix.accounts[0].is_signer);
+    assert_eq!(
+        FundraiserInstruction::unpack(&ix.data).unwrap(),
+        FundraiserInstruction::Contribute(ContributeData {
+            contribution_bump: k.contribution_bump,
+            amount: DONATION_AMOUNT,
+        })
+    );
+}
+
+#[test]
+pub fn test_client_checkout() {
+    let k = keys();
+    let ix = client::checkout(
+        &k.maker,
+        CAMPAIGN_ID,
+        &k.mint,
+        &TOKEN_PROGRAM_ID,
+        VaultKind::AssociatedToken,
+    );
+    let accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(
+        accounts[..5],
+        [
+            k.maker,
+            k.mint,
+            k.fundraiser,
+            get_associated_token_address(&k.fundraiser, &k.mint),
+            get_associated_token_address(&k.maker, &k.mint),
+        ]
+    );
+    assert!(ix.accounts[0].is_signer);
+    assert_eq!(
+        FundraiserInstruction::unpack(&ix.data).unwrap(),
+        FundraiserInstruction::Checkout
+    );
+}
+
+#[test]
+pub fn test_client_refund() {
+    let k = keys();
+    let ix = client::refund(
+        &k.contributor,
+        &k.maker,
+        CAMPAIGN_ID,
+        &k.mint,
+        &TOKEN_PROGRAM_ID,
+        VaultKind::AssociatedToken,
+        false,
+    );
+    let accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(
+        accounts[..7],
+        [
+            k.contributor,
+            k.maker,
+            k.mint,
+            k.fundraiser,
+            k.contribution,
+            get_associated_token_address(&k.contributor, &k.mint),
+            get_associated_token_address(&k.fundraiser, &k.mint),
+        ]
+    );
+    assert!(ix.accounts[0].is_signer);
+    assert_eq!(
+        FundraiserInstruction::unpack(&ix.data).unwrap(),
+        FundraiserInstruction::Refund(RefundData {
+            contribution_bump: k.contribution_bump,
+            unwrap_sol: false,
+        })
+    );
+}
+
+#[test]
+pub fn test_client_cancel() {
+    let k = keys();
+    let ix = client::cancel(
+        &k.maker,
+        CAMPAIGN_ID,
+        &k.mint,
+        &TOKEN_PROGRAM_ID,
+        VaultKind::AssociatedToken,
+    );
+    let accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(
+        accounts,
+        [
+            k.maker,
+            k.fundraiser,
+            k.mint,
+            get_associated_token_address(&k.fundraiser, &k.mint),
+            get_associated_token_address(&k.maker, &k.mint),
+            TOKEN_PROGRAM_ID,
+        ]
+    );
+    assert!(ix.accounts[0].is_signer && ix.accounts[0].is_writable);
+    assert!(ix.accounts[1].is_writable);
+    assert_eq!(
+        FundraiserInstruction::unpack(&ix.data).unwrap(),
+        FundraiserInstruction::Cancel
+    );
+}
+
+#[test]
+pub fn test_client_withdraw() {
+    let k = keys();
+    let ix = client::withdraw(
+        &k.contributor,
+        &k.maker,
+        CAMPAIGN_ID,
+        &k.mint,
+        &TOKEN_PROGRAM_ID,
+        VaultKind::AssociatedToken,
+        DONATION_AMOUNT,
+    );
+    let accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(
+        accounts[..6],
+        [
+            k.contributor,
+            k.mint,
+            k.fundraiser,
+            k.contribution,
+            get_associated_token_address(&k.contributor, &k.mint),
+            get_associated_token_address(&k.fundraiser, &k.mint),
+        ]
+    );
+    assert!(ix.accounts[0].is_signer);
+    assert_eq!(
+        FundraiserInstruction::unpack(&ix.data).unwrap(),
+        FundraiserInstruction::Withdraw(WithdrawData {
+            contribution_bump: k.contribution_bump,
+            amount: DONATION_AMOUNT,
+        })
+    );
+}
+
+#[test]
+pub fn test_client_refund_batch() {
+    let k = keys();
+    let other = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let ix = client::refund_batch(
+        &payer,
+        &k.maker,
+        CAMPAIGN_ID,
+        &k.mint,
+        &TOKEN_PROGRAM_ID,
+        VaultKind::AssociatedToken,
+        &[k.contributor, other],
+    );
+    let accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(
+        accounts[..4],
+        [
+            k.maker,
+            k.mint,
+            k.fundraiser,
+            get_associated_token_address(&k.fundraiser, &k.mint),
+        ]
+    );
+    assert_eq!(accounts[5], payer);
+    assert_eq!(
+        accounts[8..],
+        [
+            k.contribution,
+            k.contributor,
+            get_associated_token_address(&k.contributor, &k.mint),
+            client::find_contribution_address(&k.fundraiser, &other).0,
+            other,
+            get_associated_token_address(&other, &k.mint),
+        ]
+    );
+    assert!(ix.accounts[5].is_signer && ix.accounts[5].is_writable);
+    assert_eq!(ix.accounts.iter().filter(|meta| meta.is_signer).count(), 1);
+    assert_eq!(
+        FundraiserInstruction::unpack(&ix.data).unwrap(),
+        FundraiserInstruction::RefundBatch
+    );
+}
+
+#[test]
+pub fn test_client_close_contribution() {
+    let k = keys();
+    let ix = client::close_contribution(&k.contributor, &k.maker, CAMPAIGN_ID);
+    let accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(accounts, [k.contributor, k.fundraiser, k.contribution]);
+    assert!(ix.accounts[0].is_signer);
+    assert_eq!(
+        FundraiserInstruction::unpack(&ix.data).unwrap(),
+        FundraiserInstruction::CloseContribution
+    );
+}
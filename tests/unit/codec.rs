@@ -0,0 +1,181 @@
+use pinocchio::error::ProgramError;
+use pinocchio_fundraiser::{
+    instructions::{
+        ContributeData, ContributionCap, ContributionLimits, FundraiserInstruction, InitializeData,
+        InstructionDiscriminator, RefundData, WithdrawData,
+    },
+    state::{FundingMode, VaultKind, WithdrawalPolicy},
+};
+
+fn all_instructions() -> [FundraiserInstruction; 8] {
+    [
+        FundraiserInstruction::Initialize(InitializeData {
+            bump: 254,
+            campaign_id: 7,
+            amount_to_raise: 100_000_000,
+            hard_cap: 150_000_000,
+            start_ts: Some(1_000),
+            end_ts: 432_000,
+            limits: ContributionLimits {
+                min_contribution: 1_000,
+                max_per_contributor: ContributionCap::BasisPoints(1_000),
+                max_contributors: 50,
+            },
+            funding_mode: FundingMode::KeepWhatYouRaise,
+            early_checkout: true,
+            withdrawal_policy: WithdrawalPolicy::CoolingOff(3_600),
+            vault_kind: VaultKind::Pda,
+            vault_bump: 254,
+            maker_bump: 250,
+        }),
+        FundraiserInstruction::Contribute(ContributeData {
+            contribution_bump: 253,
+            amount: 500_000,
+        }),
+        FundraiserInstruction::Checkout,
+        FundraiserInstruction::Refund(RefundData {
+            contribution_bump: 252,
+            unwrap_sol: true,
+        }),
+        FundraiserInstruction::Cancel,
+        FundraiserInstruction::Withdraw(WithdrawData {
+            contribution_bump: 251,
+            amount: 250_000,
+        }),
+        FundraiserInstruction::RefundBatch,
+        FundraiserInstruction::CloseContribution,
+    ]
+}
+
+#[test]
+pub fn test_instructions_round_trip() {
+    for ix in all_instructions() {
+        assert_eq!(FundraiserInstruction::unpack(&ix.pack()).unwrap(), ix);
+    }
+}
+
+#[test]
+pub fn test_discriminators_are_stable() {
+    let expected = [
+        InstructionDiscriminator::Initialize,
+        InstructionDiscriminator::Contribute,
+        InstructionDiscriminator::Checkout,
+        InstructionDiscriminator::Refund,
+        InstructionDiscriminator::Cancel,
+        InstructionDiscriminator::Withdraw,
+        InstructionDiscriminator::RefundBatch,
+        InstructionDiscriminator::CloseContribution,
+    ];
+    for (byte, (ix, discriminator)) in all_instructions().iter().zip(expected).enumerate() {
+        assert_eq!(discriminator as u8, byte as u8);
+        assert_eq!(ix.discriminator(), discriminator);
+        assert_eq!(ix.pack()[0], byte as u8);
+        assert_eq!(
+            InstructionDiscriminator::try_from(&(byte as u8)),
+            Ok(discriminator)
+        );
+    }
+}
+
+#[test]
+pub fn test_wire_layout() {
+    let [
+        initialize,
+        contribute,
+        checkout,
+        refund,
+        cancel,
+        withdraw,
+        refund_batch,
+        close_contribution,
+    ] = all_instructions();
+
+    let mut expected = vec![0, 254];
+    expected.extend(7u64.to_le_bytes());
+    expected.extend(100_000_000u64.to_le_bytes());
+    expected.extend(150_000_000u64.to_le_bytes());
+    expected.push(1);
+    expected.extend(1_000i64.to_le_bytes());
+    expected.extend(432_000i64.to_le_bytes());
+    expected.extend(1_000u64.to_le_bytes());
+    expected.push(1);
+    expected.extend(1_000u16.to_le_bytes());
+    expected.extend(50u32.to_le_bytes());
+    expected.push(1);
+    expected.push(1);
+    expected.push(2);
+    expected.extend(3_600i64.to_le_bytes());
+    expected.push(1);
+    expected.push(254);
+    expected.push(250);
+    assert_eq!(initialize.pack(), expected);
+
+    let FundraiserInstruction::Initialize(data) = initialize else {
+        unreachable!()
+    };
+    let immediate = FundraiserInstruction::Initialize(InitializeData {
+        start_ts: None,
+        limits: ContributionLimits {
+            max_per_contributor: ContributionCap::Amount(5_000_000),
+            ..data.limits
+        },
+        funding_mode: FundingMode::AllOrNothing,
+        early_checkout: false,
+        withdrawal_policy: WithdrawalPolicy::Disabled,
+        ..data
+    });
+    let mut expected = vec![0, 254];
+    expected.extend(7u64.to_le_bytes());
+    expected.extend(100_000_000u64.to_le_bytes());
+    expected.extend(150_000_000u64.to_le_bytes());
+    expected.push(0);
+    expected.extend(432_000i64.to_le_bytes());
+    expected.extend(1_000u64.to_le_bytes());
+    expected.push(0);
+    expected.extend(5_000_000u64.to_le_bytes());
+    expected.extend(50u32.to_le_bytes());
+    expected.push(0);
+    expected.push(0);
+    expected.push(0);
+    expected.push(1);
+    expected.push(254);
+    expected.push(250);
+    assert_eq!(immediate.pack(), expected);
+    assert_eq!(FundraiserInstruction::unpack(&expected).unwrap(), immediate);
+
+    let mut expected = vec![1, 253];
+    expected.extend(500_000u64.to_le_bytes());
+    assert_eq!(contribute.pack(), expected);
+
+    assert_eq!(checkout.pack(), vec![2]);
+    assert_eq!(refund.pack(), vec![3, 252, 1]);
+    assert_eq!(cancel.pack(), vec![4]);
+
+    let mut expected = vec![5, 251];
+    expected.extend(250_000u64.to_le_bytes());
+    assert_eq!(withdraw.pack(), expected);
+
+    assert_eq!(refund_batch.pack(), vec![6]);
+    assert_eq!(close_contribution.pack(), vec![7]);
+}
+
+#[test]
+pub fn test_unpack_rejects_malformed_data() {
+    assert_eq!(
+        FundraiserInstruction::unpack(&[]),
+        Err(ProgramError::InvalidInstructionData)
+    );
+    assert_eq!(
+        FundraiserInstruction::unpack(&[8]),
+        Err(ProgramError::InvalidInstructionData)
+    );
+    for ix in all_instructions() {
+        let data = ix.pack();
+        if data.len() > 1 {
+            assert_eq!(
+                FundraiserInstruction::unpack(&data[..data.len() - 1]),
+                Err(ProgramError::InvalidInstructionData)
+            );
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use pinocchio_fundraiser::{
+    error::FundraiserError,
+    limits::{ContributionOutcome, evaluate_contribution},
+    state::Fundraiser,
+};
+
+use crate::fixtures::fundraiser_state;
+
+const MIN: u64 = 10;
+const MAX: u64 = 100;
+
+fn fundraiser(max_contributors: u32, contributor_count: u32) -> Fundraiser {
+    Fundraiser {
+        amount_to_raise: 1_000u64.to_le_bytes(),
+        min_contribution: MIN.to_le_bytes(),
+        max_contribution: MAX.to_le_bytes(),
+        max_contributors: max_contributors.to_le_bytes(),
+        contributor_count: contributor_count.to_le_bytes(),
+        start_ts: 0i64.to_le_bytes(),
+        end_ts: 1i64.to_le_bytes(),
+        ..fundraiser_state()
+    }
+}
+
+#[test]
+pub fn test_new_contributor() {
+    let state = fundraiser(5, 2);
+    assert_eq!(
+        evaluate_contribution(&state, None, MAX),
+        Ok(ContributionOutcome {
+            accepted_amount: MAX,
+            contributor_total: MAX,
+            contributor_count: 3,
+        })
+    );
+    assert_eq!(
+        evaluate_contribution(&state, None, MAX + 1),
+        Err(FundraiserError::ContributionLimitExceeded.into())
+    );
+    assert_eq!(
+        evaluate_contribution(&state, None, MIN - 1),
+        Err(FundraiserError::ContributionTooSmall.into())
+    );
+    assert_eq!(
+        evaluate_contribution(&fundraiser(5, 5), None, MIN),
+        Err(FundraiserError::ContributorLimitReached.into())
+    );
+}
+
+#[test]
+pub fn test_existing_contributor() {
+    let state = fundraiser(5, 5);
+    // already counted, so a full campaign still accepts top-ups
+    assert_eq!(
+        evaluate_contribution(&state, Some(40), MAX - 40),
+        Ok(ContributionOutcome {
+            accepted_amount: MAX - 40,
+            contributor_total: MAX,
+            contributor_count: 5,
+        })
+    );
+    assert_eq!(
+        evaluate_contribution(&state, Some(40), MAX - 39),
+        Err(FundraiserError::ContributionLimitExceeded.into())
+    );
+    assert_eq!(
+        evaluate_contribution(&state, Some(40), MIN - 1),
+        Err(FundraiserError::ContributionTooSmall.into())
+    );
+    assert_eq!(
+        evaluate_contribution(&state, Some(u64::MAX), MIN),
+        Err(FundraiserError::ArithmeticOverflow.into())
+    );
+}
+
+#[test]
+pub fn test_new_and_existing_contributors_share_the_cap() {
+    let state = fundraiser(5, 1);
+    for amount in [MIN - 1, MIN, MAX, MAX + 1] {
+        let first = evaluate_contribution(&state, None, amount).map(|o| o.contributor_total);
+        let top_up = evaluate_contribution(&state, Some(0), amount).map(|o| o.contributor_total);
+        assert_eq!(first, top_up);
+    }
+}
+
+#[test]
+pub fn test_hard_cap_trims_or_rejects() {
+    let mut state = fundraiser(5, 1);
+    state.hard_cap = 500u64.to_le_bytes();
+    state.current_amount = 470u64.to_le_bytes();
+
+    // trimmed to the room left, and only the accepted part counts towards the
+    // contributor's cap
+    assert_eq!(
+        evaluate_contribution(&state, Some(80), 50),
+        Err(FundraiserError::ContributionLimitExceeded.into())
+    );
+    assert_eq!(
+        evaluate_contribution(&state, Some(60), 50),
+        Ok(ContributionOutcome {
+            accepted_amount: 30,
+            contributor_total: 90,
+            contributor_count: 1,
+        })
+    );
+    assert_eq!(
+        evaluate_contribution(&state, Some(40), 50),
+        Ok(ContributionOutcome {
+            accepted_amount: 30,
+            contributor_total: 70,
+            contributor_count: 1,
+        })
+    );
+    // the minimum applies to what was asked for, so the last contributor can fill the cap
+    state.current_amount = 495u64.to_le_bytes();
+    assert_eq!(
+        evaluate_contribution(&state, None, MIN),
+        Ok(ContributionOutcome {
+            accepted_amount: 5,
+            contributor_total: 5,
+            contributor_count: 2,
+        })
+    );
+
+    state.current_amount = 500u64.to_le_bytes();
+    assert_eq!(
+        evaluate_contribution(&state, Some(40), MIN),
+        Err(FundraiserError::HardCapReached.into())
+    );
+}
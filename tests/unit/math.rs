@@ -0,0 +1,36 @@
+use pinocchio::error::ProgramError;
+use pinocchio_fundraiser::{error::FundraiserError, math};
+
+fn overflow() -> ProgramError {
+    FundraiserError::ArithmeticOverflow.into()
+}
+
+#[test]
+pub fn test_checked_add() {
+    assert_eq!(math::checked_add(1, 2), Ok(3));
+    assert_eq!(math::checked_add(u64::MAX - 1, 1), Ok(u64::MAX));
+    assert_eq!(math::checked_add(u64::MAX, 1), Err(overflow()));
+}
+
+#[test]
+pub fn test_checked_sub() {
+    assert_eq!(math::checked_sub(3, 2), Ok(1));
+    assert_eq!(math::checked_sub(2, 2), Ok(0));
+    assert_eq!(math::checked_sub(2, 3), Err(overflow()));
+}
+
+#[test]
+pub fn test_checked_pow() {
+    assert_eq!(math::checked_pow(3, 6), Ok(729));
+    assert_eq!(math::checked_pow(3, 0), Ok(1));
+    assert_eq!(math::checked_pow(3, 41), Err(overflow()));
+}
+
+#[test]
+pub fn test_checked_mul_div() {
+    assert_eq!(math::checked_mul_div(100_000_000, 10, 100), Ok(10_000_000));
+    // the intermediate product doesn't have to fit in a u64
+    assert_eq!(math::checked_mul_div(u64::MAX, 10, 100), Ok(u64::MAX / 10));
+    assert_eq!(math::checked_mul_div(u64::MAX, 2, 1), Err(overflow()));
+    assert_eq!(math::checked_mul_div(1, 1, 0), Err(overflow()));
+}
@@ -0,0 +1,6 @@
+pub mod client;
+pub mod codec;
+pub mod limits;
+pub mod math;
+pub mod status;
+pub mod time;
@@ -0,0 +1,126 @@
+use pinocchio::error::ProgramError;
+use pinocchio_fundraiser::{
+    error::FundraiserError,
+    state::{FundingMode, Fundraiser, FundraiserStatus, WithdrawalPolicy},
+};
+
+use crate::fixtures::fundraiser_state;
+
+const START_TS: i64 = 1_000;
+const END_TS: i64 = 2_000;
+const GOAL: u64 = 100;
+
+fn fundraiser(status: FundraiserStatus, current_amount: u64) -> Fundraiser {
+    Fundraiser {
+        amount_to_raise: GOAL.to_le_bytes(),
+        current_amount: current_amount.to_le_bytes(),
+        min_contribution: 1u64.to_le_bytes(),
+        max_contribution: GOAL.to_le_bytes(),
+        max_contributors: 10u32.to_le_bytes(),
+        start_ts: START_TS.to_le_bytes(),
+        end_ts: END_TS.to_le_bytes(),
+        status: status as u8,
+        ..fundraiser_state()
+    }
+}
+
+#[test]
+pub fn test_status_values_are_stable() {
+    use FundraiserStatus::*;
+    for (byte, status) in [
+        Active, Cancelled, Pending, Succeeded, Failed, Withdrawn, Closed,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        assert_eq!(status as u8, byte as u8);
+        assert_eq!(FundraiserStatus::try_from(byte as u8), Ok(status));
+    }
+    assert_eq!(
+        FundraiserStatus::try_from(7),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
+
+#[test]
+pub fn test_transitions() {
+    use FundraiserStatus::*;
+    assert!(Pending.can_transition_to(Active));
+    assert!(Active.can_transition_to(Cancelled));
+    assert!(Succeeded.can_transition_to(Withdrawn));
+    assert!(Failed.can_transition_to(Closed));
+
+    // no double checkout, no checkout of a failed or cancelled campaign
+    assert!(!Withdrawn.can_transition_to(Withdrawn));
+    assert!(!Failed.can_transition_to(Withdrawn));
+    assert!(!Cancelled.can_transition_to(Withdrawn));
+    // finished campaigns can't be cancelled or reopened
+    assert!(!Succeeded.can_transition_to(Cancelled));
+    assert!(!Closed.can_transition_to(Active));
+
+    let mut state = fundraiser(Withdrawn, GOAL);
+    assert_eq!(
+        state.transition(Withdrawn),
+        Err(FundraiserError::InvalidStatusTransition.into())
+    );
+    assert_eq!(state.transition(Closed), Ok(()));
+    assert_eq!(state.status(), Ok(Closed));
+}
+
+#[test]
+pub fn test_sync_status_follows_the_clock() {
+    use FundraiserStatus::*;
+    let mut state = fundraiser(Pending, 0);
+    assert_eq!(state.sync_status(START_TS - 1), Ok(Pending));
+    assert_eq!(state.sync_status(START_TS), Ok(Active));
+    assert_eq!(state.sync_status(END_TS - 1), Ok(Active));
+    assert_eq!(state.sync_status(END_TS), Ok(Failed));
+
+    let mut state = fundraiser(Active, GOAL);
+    assert_eq!(state.sync_status(END_TS), Ok(Succeeded));
+
+    // a pending campaign nobody touched during its window still settles
+    let mut state = fundraiser(Pending, 0);
+    assert_eq!(state.sync_status(END_TS + 1), Ok(Failed));
+
+    // statuses set by instructions are left alone
+    for status in [Cancelled, Withdrawn, Closed] {
+        let mut state = fundraiser(status, GOAL);
+        assert_eq!(state.sync_status(END_TS), Ok(status));
+    }
+}
+
+#[test]
+pub fn test_sync_status_depends_on_funding_mode() {
+    use FundraiserStatus::*;
+    let mut state = fundraiser(Active, GOAL - 1);
+    assert_eq!(state.sync_status(END_TS), Ok(Failed));
+
+    let mut state = fundraiser(Active, 1);
+    state.funding_mode = FundingMode::KeepWhatYouRaise as u8;
+    assert_eq!(state.sync_status(END_TS), Ok(Succeeded));
+
+    // nothing raised, nothing to keep
+    let mut state = fundraiser(Active, 0);
+    state.funding_mode = FundingMode::KeepWhatYouRaise as u8;
+    assert_eq!(state.sync_status(END_TS), Ok(Failed));
+}
+
+#[test]
+pub fn test_withdrawal_policy_round_trips() {
+    let mut state = fundraiser(FundraiserStatus::Active, 0);
+    assert_eq!(state.withdrawal_policy(), Ok(WithdrawalPolicy::Disabled));
+    for policy in [
+        WithdrawalPolicy::Anytime,
+        WithdrawalPolicy::CoolingOff(86_400),
+        WithdrawalPolicy::Disabled,
+    ] {
+        state.set_withdrawal_policy(policy);
+        assert_eq!(state.withdrawal_policy(), Ok(policy));
+    }
+    state.withdrawal_policy = 3;
+    assert_eq!(
+        state.withdrawal_policy(),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
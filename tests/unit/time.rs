@@ -0,0 +1,42 @@
+use pinocchio_fundraiser::{error::FundraiserError, time};
+
+#[test]
+pub fn test_validate_window_boundaries() {
+    assert_eq!(time::validate_window(1_000, 1_001), Ok(()));
+    assert_eq!(
+        time::validate_window(1_000, 1_000),
+        Err(FundraiserError::InvalidTimestamp.into())
+    );
+    assert_eq!(
+        time::validate_window(1_000, 999),
+        Err(FundraiserError::InvalidTimestamp.into())
+    );
+    assert_eq!(time::validate_window(i64::MIN, i64::MAX), Ok(()));
+}
+
+#[test]
+pub fn test_has_started_boundaries() {
+    let start_ts = 1_000;
+    assert!(!time::has_started(start_ts, start_ts - 1));
+    assert!(time::has_started(start_ts, start_ts));
+    assert!(time::has_started(start_ts, start_ts + 1));
+}
+
+#[test]
+pub fn test_has_ended_boundaries() {
+    let end_ts = 1_000;
+    assert!(!time::has_ended(end_ts, end_ts - 1));
+    assert!(time::has_ended(end_ts, end_ts));
+    assert!(time::has_ended(end_ts, end_ts + 1));
+    assert!(!time::has_ended(i64::MAX, i64::MAX - 1));
+    assert!(time::has_ended(i64::MIN, i64::MIN));
+}
+
+#[test]
+pub fn test_has_ended_supports_long_and_short_campaigns() {
+    let start = 1_000;
+    // campaigns are no longer limited to whole days or to 255 days
+    assert!(!time::has_ended(start + 3_600, start + 3_599));
+    assert!(time::has_ended(start + 3_600, start + 3_600));
+    assert!(!time::has_ended(start + 300 * 86_400, start + 299 * 86_400));
+}